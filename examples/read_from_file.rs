@@ -12,7 +12,7 @@ fn main() {
 		eprintln!("Please provide an input file path!");
 		exit(1);
 	}
-	match input_parser::parse_input_file(&args[1]) {
+	match input_parser::parse_input_file::<i64>(&args[1]) {
 		Ok(task_list) => {
 			match scheduler.fill_tasklist(task_list) {
 				Ok(()) => {},
@@ -0,0 +1,651 @@
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::process::exit;
+use std::thread;
+use std::time::Duration;
+
+extern crate cpm_rs;
+
+use cpm_rs::{csv, dot, html, input_parser, svg};
+use cpm_rs::customtask::CustomTask;
+use cpm_rs::diagram::{self, DiagramOptions};
+use cpm_rs::input_parser::{ParseOptions, ParsedInput, TaskOrder, WriteOptions};
+use cpm_rs::repl::Repl;
+use cpm_rs::scheduler::Scheduler;
+use cpm_rs::summary::ScheduleSummary;
+use cpm_rs::units::UnitConfig;
+use cpm_rs::watch::{FileWatcher, ScheduleDiff};
+
+/// Everything went fine.
+const EXIT_OK: i32 = 0;
+/// The input could not be read, parsed or scheduled.
+const EXIT_ERROR: i32 = 1;
+/// Wrong command line arguments.
+const EXIT_USAGE: i32 = 2;
+/// The input is readable, but it has cycles or dangling dependencies.
+const EXIT_INVALID: i32 = 3;
+
+static USAGE: &str = "Usage: cpm <COMMAND> [OPTIONS] [FILE]
+       cpm watch [OPTIONS] FILE...
+       cpm repl [OPTIONS] [FILE]
+       cpm tui [OPTIONS] FILE
+
+Reads task definitions from FILE, or from the standard input if FILE is
+missing or '-'. Files ending in '.json', '.yaml', '.yml' or '.toml' are read
+as project files of that format.
+
+Commands:
+    schedule       Print the calculated timings of every task.
+    critical       Print the critical paths.
+    validate       Check for dependency cycles and dangling dependencies.
+    parallelism    Print the maximum number of parallel tasks.
+    export         Print the schedule in the format given by --format.
+    watch          Re-schedule the tasks of all FILEs whenever one of them
+                   changes and print what changed.
+    repl           Load FILE, or no tasks, into an interactive shell for
+                   what-if changes. Type 'help' there for the commands.
+    tui            Full-screen Gantt viewer of FILE with live editing.
+    fmt            Print FILE in the canonical format, keeping the comments.
+
+Options:
+    --int                 Integer durations (default).
+    --float               Floating point durations.
+    --unit <UNIT>         Time unit of the schedule and of the durations
+                          without a unit: h, d (default) or w.
+    --hours-per-day <N>   Hours of a day for the conversions (default 8).
+    --days-per-week <N>   Days of a week for the conversions (default 5).
+    --format <FORMAT>     Export format: dot, json, csv, svg, html, mermaid, plantuml.
+    --interval <MS>       Polling interval of watch in milliseconds (default 500).
+    --order <ORDER>       Task order of fmt: insertion (default), alphabetical, topological.
+    --width <COLUMNS>     Wrap longer tasks in fmt, 0 turns it off (default 80).
+    --write               Overwrite FILE with the output of fmt.
+    -h, --help            Print this help.
+";
+
+/// Parsed command line arguments.
+struct Arguments {
+	command: String,
+	float: bool,
+	format: Option<String>,
+	interval: u64,
+	order: TaskOrder,
+	width: usize,
+	write: bool,
+	units: UnitConfig,
+	inputs: Vec<String>,
+}
+
+/// Duration types of the command line.
+trait CliDuration: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = Self>
+	+ std::ops::Add<Output = Self>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ std::str::FromStr
+{
+	fn json_report(scheduler: &Scheduler<Self>) -> Result<String, String>;
+	fn json_project(contents: &str) -> Result<Vec<CustomTask<Self>>, String>;
+	fn yaml_project(contents: &str) -> Result<Vec<CustomTask<Self>>, String>;
+	fn toml_project(contents: &str) -> Result<Vec<CustomTask<Self>>, String>;
+}
+
+impl CliDuration for i64 {
+	fn json_report(scheduler: &Scheduler<Self>) -> Result<String, String> {
+		json_report(scheduler)
+	}
+
+	fn json_project(contents: &str) -> Result<Vec<CustomTask<Self>>, String> {
+		json_project(contents)
+	}
+
+	fn yaml_project(contents: &str) -> Result<Vec<CustomTask<Self>>, String> {
+		yaml_project(contents)
+	}
+
+	fn toml_project(contents: &str) -> Result<Vec<CustomTask<Self>>, String> {
+		toml_project(contents)
+	}
+}
+
+impl CliDuration for f64 {
+	fn json_report(scheduler: &Scheduler<Self>) -> Result<String, String> {
+		json_report(scheduler)
+	}
+
+	fn json_project(contents: &str) -> Result<Vec<CustomTask<Self>>, String> {
+		json_project(contents)
+	}
+
+	fn yaml_project(contents: &str) -> Result<Vec<CustomTask<Self>>, String> {
+		yaml_project(contents)
+	}
+
+	fn toml_project(contents: &str) -> Result<Vec<CustomTask<Self>>, String> {
+		toml_project(contents)
+	}
+}
+
+#[cfg(feature = "json")]
+fn json_report<T>(scheduler: &Scheduler<T>) -> Result<String, String>
+where T: CliDuration + serde::Serialize
+{
+	cpm_rs::json::write_report(scheduler)
+}
+
+#[cfg(not(feature = "json"))]
+fn json_report<T>(_scheduler: &Scheduler<T>) -> Result<String, String>
+where T: CliDuration
+{
+	Err("JSON export needs the `json` feature of cpm-rs.".to_string())
+}
+
+#[cfg(feature = "json")]
+fn json_project<T>(contents: &str) -> Result<Vec<CustomTask<T>>, String>
+where T: CliDuration + serde::de::DeserializeOwned
+{
+	cpm_rs::json::parse_str(contents)
+}
+
+#[cfg(not(feature = "json"))]
+fn json_project<T>(_contents: &str) -> Result<Vec<CustomTask<T>>, String>
+where T: CliDuration
+{
+	Err("JSON projects need the `json` feature of cpm-rs.".to_string())
+}
+
+#[cfg(feature = "yaml")]
+fn yaml_project<T>(contents: &str) -> Result<Vec<CustomTask<T>>, String>
+where T: CliDuration + serde::de::DeserializeOwned
+{
+	cpm_rs::yaml::parse_str(contents)
+}
+
+#[cfg(not(feature = "yaml"))]
+fn yaml_project<T>(_contents: &str) -> Result<Vec<CustomTask<T>>, String>
+where T: CliDuration
+{
+	Err("YAML projects need the `yaml` feature of cpm-rs.".to_string())
+}
+
+#[cfg(feature = "toml")]
+fn toml_project<T>(contents: &str) -> Result<Vec<CustomTask<T>>, String>
+where T: CliDuration + serde::de::DeserializeOwned
+{
+	cpm_rs::toml::parse_str(contents)
+}
+
+#[cfg(not(feature = "toml"))]
+fn toml_project<T>(_contents: &str) -> Result<Vec<CustomTask<T>>, String>
+where T: CliDuration
+{
+	Err("TOML projects need the `toml` feature of cpm-rs.".to_string())
+}
+
+#[cfg(feature = "tui")]
+fn tui<T>(arguments: &Arguments) -> Result<(), (i32, String)>
+where T: CliDuration
+{
+	let error = |e: String| (EXIT_ERROR, e);
+	let input = &arguments.inputs[0];
+	let tasks = parse_tasks(&read_input(Some(input)).map_err(error)?, Some(input), &arguments.units)?;
+	let mut scheduler = Scheduler::<T>::new();
+	scheduler.fill_tasklist(tasks).map_err(|e| (EXIT_INVALID, e))?;
+	check_dependencies(&scheduler)?;
+	cpm_rs::tui::run(scheduler, input.clone(), arguments.units).map_err(error)
+}
+
+#[cfg(not(feature = "tui"))]
+fn tui<T>(_arguments: &Arguments) -> Result<(), (i32, String)>
+where T: CliDuration
+{
+	Err((EXIT_ERROR, "The terminal UI needs the `tui` feature of cpm-rs.".to_string()))
+}
+
+fn main() {
+	let arguments = match parse_arguments(env::args().skip(1).collect()) {
+		Ok(arguments) => arguments,
+		Err(e) => {
+			eprintln!("Error: {}\n\n{}", e, USAGE);
+			exit(EXIT_USAGE);
+		},
+	};
+	if arguments.command == "watch" {
+		if arguments.float {
+			watch::<f64>(&arguments);
+		} else {
+			watch::<i64>(&arguments);
+		}
+	}
+	if arguments.command == "repl" || arguments.command == "tui" {
+		let result = match (arguments.command.as_str(), arguments.float) {
+			("repl", true) => repl::<f64>(&arguments),
+			("repl", false) => repl::<i64>(&arguments),
+			(_, true) => tui::<f64>(&arguments),
+			(_, false) => tui::<i64>(&arguments),
+		};
+		if let Err((code, e)) = result {
+			eprintln!("Error: {}", e);
+			exit(code);
+		}
+		exit(EXIT_OK);
+	}
+	let contents = match read_input(arguments.inputs.first()) {
+		Ok(contents) => contents,
+		Err(e) => {
+			eprintln!("Error: {}", e);
+			exit(EXIT_ERROR);
+		},
+	};
+	let result = if arguments.float {
+		run::<f64>(&arguments, &contents)
+	} else {
+		run::<i64>(&arguments, &contents)
+	};
+	match result {
+		Ok(output) => {
+			print!("{}", output);
+			exit(EXIT_OK);
+		},
+		Err((code, e)) => {
+			eprintln!("Error: {}", e);
+			exit(code);
+		},
+	}
+}
+
+fn parse_arguments(args: Vec<String>) -> Result<Arguments, String> {
+	let mut arguments = Arguments {
+		command: String::new(),
+		float: false,
+		format: None,
+		interval: 500,
+		order: TaskOrder::Insertion,
+		width: 80,
+		write: false,
+		units: UnitConfig::default(),
+		inputs: vec!{},
+	};
+	let mut args = args.into_iter();
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"-h" | "--help" => {
+				print!("{}", USAGE);
+				exit(EXIT_OK);
+			},
+			"--int" => { arguments.float = false; },
+			"--float" => { arguments.float = true; },
+			"--format" => {
+				arguments.format = Some(args.next().ok_or("missing value of --format")?);
+			},
+			_ if arg.starts_with("--format=") => {
+				arguments.format = Some(arg["--format=".len()..].to_string());
+			},
+			"--interval" => {
+				let interval = args.next().ok_or("missing value of --interval")?;
+				arguments.interval = interval.parse()
+					.map_err(|_| format!("invalid interval: {}", interval))?;
+			},
+			"--order" => {
+				arguments.order = args.next().ok_or("missing value of --order")?.parse()?;
+			},
+			"--width" => {
+				let width = args.next().ok_or("missing value of --width")?;
+				arguments.width = width.parse()
+					.map_err(|_| format!("invalid width: {}", width))?;
+			},
+			"--write" => { arguments.write = true; },
+			"--unit" => {
+				arguments.units.base = args.next().ok_or("missing value of --unit")?.parse()?;
+			},
+			"--hours-per-day" | "--days-per-week" => {
+				let value = args.next().ok_or(format!("missing value of {}", arg))?;
+				let value = value.parse::<f64>().ok()
+					.filter(|value| value.is_finite() && *value > 0.0)
+					.ok_or(format!("invalid value of {}: {}", arg, value))?;
+				if arg == "--hours-per-day" {
+					arguments.units.hours_per_day = value;
+				} else {
+					arguments.units.days_per_week = value;
+				}
+			},
+			_ if arg.starts_with('-') && arg != "-" => {
+				return Err(format!("unknown option: {}", arg));
+			},
+			_ if arguments.command.is_empty() => { arguments.command = arg; },
+			_ if arguments.inputs.is_empty() || arguments.command == "watch" => {
+				arguments.inputs.push(arg);
+			},
+			_ => { return Err(format!("unexpected argument: {}", arg)); },
+		}
+	}
+	match arguments.command.as_str() {
+		"" => Err("missing command".to_string()),
+		"export" if arguments.format.is_none() => Err("export needs --format".to_string()),
+		"watch" if arguments.inputs.is_empty() || arguments.inputs.iter().any(|input| input == "-") => {
+			Err("watch needs input files".to_string())
+		},
+		"watch" => Ok(arguments),
+		"repl" if arguments.inputs.first().is_some_and(|input| input == "-") => {
+			Err("repl reads the commands from the standard input, it needs a file".to_string())
+		},
+		"repl" => Ok(arguments),
+		"tui" if arguments.inputs.first().is_none_or(|input| input == "-") => {
+			Err("tui needs an input file".to_string())
+		},
+		"tui" => Ok(arguments),
+		"fmt" if project_format(arguments.inputs.first()).is_some() => {
+			Err("fmt formats task files, not project files".to_string())
+		},
+		"fmt" if arguments.write && arguments.inputs.first().is_none_or(|input| input == "-") => {
+			Err("--write needs an input file".to_string())
+		},
+		"fmt" => Ok(arguments),
+		"schedule" | "critical" | "validate" | "parallelism" | "export" => Ok(arguments),
+		command => Err(format!("unknown command: {}", command)),
+	}
+}
+
+fn read_input(input: Option<&String>) -> Result<String, String> {
+	match input.map(|input| input.as_str()) {
+		None | Some("-") => {
+			let mut contents = String::new();
+			std::io::stdin().read_to_string(&mut contents)
+				.map_err(|e| format!("Could not read standard input: {}", e))?;
+			Ok(contents)
+		},
+		Some(filename) => fs::read_to_string(filename)
+			.map_err(|e| format!("Could not read file: {}\n\r{}", filename, e)),
+	}
+}
+
+fn run<T>(arguments: &Arguments, contents: &str) -> Result<String, (i32, String)>
+where T: CliDuration
+{
+	if arguments.command == "fmt" {
+		return fmt::<T>(arguments, contents);
+	}
+	let mut scheduler = Scheduler::<T>::new();
+	let tasks = parse_tasks(contents, arguments.inputs.first(), &arguments.units)?;
+	scheduler.fill_tasklist(tasks).map_err(|e| (EXIT_INVALID, e))?;
+
+	if arguments.command == "validate" {
+		return validate(&scheduler);
+	}
+	check_dependencies(&scheduler)?;
+	scheduler.schedule().map_err(|e| (EXIT_INVALID, e))?;
+	let error = |e: String| (EXIT_ERROR, e);
+	match arguments.command.as_str() {
+		"schedule" => schedule_table(&scheduler).map_err(error),
+		"critical" => {
+			let mut output = String::new();
+			for path in scheduler.get_critical_paths() {
+				output.push_str(&format!("{} ({})\n", path.get_task_ids().join(" -> "), path.get_dur()));
+			}
+			Ok(output)
+		},
+		"parallelism" => Ok(format!("{}\n", scheduler.get_parallelism().map_err(error)?)),
+		_ => export(&scheduler, arguments.format.as_deref().unwrap_or_default()),
+	}
+}
+
+/// Format of a project file by it's extension, `None` for task files.
+fn project_format(input: Option<&String>) -> Option<&'static str> {
+	let extension = input?.rsplit_once('.')?.1;
+	match extension {
+		"json" => Some("json"),
+		"yaml" | "yml" => Some("yaml"),
+		"toml" => Some("toml"),
+		_ => None,
+	}
+}
+
+/// Parses the contents of an input, errors are named after the input file.
+fn parse_tasks<T>(contents: &str, input: Option<&String>, units: &UnitConfig) -> Result<Vec<CustomTask<T>>, (i32, String)>
+where T: CliDuration
+{
+	parse_tasks_with_sources(contents, input, units, &mut vec!{})
+}
+
+/// Parses like `parse_tasks` and adds the names of the included files that
+/// were read to `sources`, also when the parsing fails.
+fn parse_tasks_with_sources<T>(
+	contents: &str
+	, input: Option<&String>
+	, units: &UnitConfig
+	, sources: &mut Vec<String>
+) -> Result<Vec<CustomTask<T>>, (i32, String)>
+where T: CliDuration
+{
+	let filename = input.filter(|input| *input != "-");
+	if let Some(format) = project_format(input) {
+		let tasks = match format {
+			"json" => T::json_project(contents),
+			"yaml" => T::yaml_project(contents),
+			_ => T::toml_project(contents),
+		};
+		let source_name = filename.map_or("<stdin>", |filename| filename.as_str());
+		return tasks.map_err(|e| (EXIT_ERROR, format!("{}: {}", source_name, e)));
+	}
+	// Includes of a file are relative to it, the ones of stdin to the current directory.
+	let options = ParseOptions { units: *units, ..ParseOptions::default() };
+	let parsed = input_parser::parse_input_with(contents, filename.map(|filename| filename.as_str()), &options)
+		.with_source_name("<stdin>");
+	for name in parsed.sources.keys() {
+		if !sources.contains(name) {
+			sources.push(name.clone());
+		}
+	}
+	if parsed.diagnostics.is_empty() {
+		return Ok(parsed.tasks);
+	}
+	Err((EXIT_ERROR, parse_errors(&parsed, contents)))
+}
+
+/// Counts and renders the diagnostics of a parsed input.
+fn parse_errors<T>(parsed: &ParsedInput<T>, contents: &str) -> String
+where T: CliDuration
+{
+	let rendered = parsed.render_diagnostics(contents);
+	format!(
+		"{} parse error{}\n\n{}"
+		, parsed.diagnostics.len()
+		, if parsed.diagnostics.len() == 1 { "" } else { "s" }
+		, rendered.join("\n").trim_end()
+	)
+}
+
+/// Writes the input in the canonical format. The included files are not
+/// read, their directives are kept.
+fn fmt<T>(arguments: &Arguments, contents: &str) -> Result<String, (i32, String)>
+where T: CliDuration
+{
+	let filename = arguments.inputs.first().filter(|input| *input != "-");
+	let options = ParseOptions { units: arguments.units, follow_includes: false };
+	let parsed = input_parser::parse_input_with::<T>(contents, filename.map(|filename| filename.as_str()), &options)
+		.with_source_name("<stdin>");
+	if !parsed.diagnostics.is_empty() {
+		return Err((EXIT_ERROR, parse_errors(&parsed, contents)));
+	}
+	let options = WriteOptions {
+		order: arguments.order,
+		width: arguments.width,
+		comments: true,
+		units: arguments.units,
+	};
+	let output = input_parser::write_parsed(&parsed, &options);
+	match filename {
+		Some(filename) if arguments.write => {
+			fs::write(filename, output)
+				.map_err(|e| (EXIT_ERROR, format!("Could not write file: {}\n\r{}", filename, e)))?;
+			Ok(String::new())
+		},
+		_ => Ok(output),
+	}
+}
+
+/// Re-schedules the tasks of the input files on every change until interrupted.
+/// Errors are printed, and watching goes on until the files are fixed.
+fn watch<T>(arguments: &Arguments) -> !
+where T: CliDuration
+{
+	let interval = Duration::from_millis(arguments.interval);
+	let mut watcher = FileWatcher::new(arguments.inputs.clone(), interval);
+	let mut previous: Option<Scheduler<T>> = None;
+	loop {
+		// The included files are watched too, they may change on every read.
+		let mut paths = arguments.inputs.clone();
+		let loaded = load_schedule::<T>(&arguments.inputs, &arguments.units, &mut paths);
+		watcher.set_paths(paths);
+		match loaded {
+			Ok(scheduler) => {
+				let output = match &previous {
+					Some(old) => ScheduleDiff::between(old, &scheduler).map(|diff| diff.to_string()),
+					None => ScheduleSummary::from_scheduler(&scheduler).map(|summary| summary.to_string()),
+				};
+				match output {
+					Ok(output) => print!("{}", output),
+					Err(e) => eprintln!("Error: {}", e),
+				}
+				previous = Some(scheduler);
+			},
+			Err(e) => eprintln!("Error: {}", e),
+		}
+		let changed = watcher.wait_for_change();
+		// Editors may save in multiple steps, the next poll collects the rest.
+		thread::sleep(interval);
+		watcher.poll();
+		println!("\n--- changed: {}", changed.join(", "));
+	}
+}
+
+/// Runs the interactive shell on the standard input and output.
+fn repl<T>(arguments: &Arguments) -> Result<(), (i32, String)>
+where T: CliDuration
+{
+	let error = |e: String| (EXIT_ERROR, e);
+	let tasks = match arguments.inputs.first() {
+		Some(input) => parse_tasks(&read_input(Some(input)).map_err(error)?, Some(input), &arguments.units)?,
+		None => vec!{},
+	};
+	let mut scheduler = Scheduler::<T>::new();
+	scheduler.fill_tasklist(tasks.clone()).map_err(|e| (EXIT_INVALID, e))?;
+	check_dependencies(&scheduler)?;
+	// `save` writes the task file format, so it must not overwrite a project file.
+	let path = arguments.inputs.first().filter(|input| project_format(Some(input)).is_none()).cloned();
+	let mut repl = Repl::<T>::new(tasks, path).map_err(|e| (EXIT_INVALID, e))?;
+	repl.set_units(arguments.units);
+	println!("Type 'help' for the commands, 'quit' to leave.");
+	repl.run(std::io::stdin().lock(), &mut std::io::stdout()).map_err(error)
+}
+
+/// Parses all the files into one task list and schedules it. The included
+/// files that were read are added to `sources`.
+fn load_schedule<T>(inputs: &[String], units: &UnitConfig, sources: &mut Vec<String>) -> Result<Scheduler<T>, String>
+where T: CliDuration
+{
+	let mut task_list: Vec<CustomTask<T>> = vec!{};
+	for input in inputs {
+		let contents = read_input(Some(input))?;
+		task_list.extend(parse_tasks_with_sources(&contents, Some(input), units, sources).map_err(|(_, e)| e)?);
+	}
+	let mut scheduler = Scheduler::<T>::new();
+	scheduler.fill_tasklist(task_list)?;
+	check_dependencies(&scheduler).map_err(|(_, e)| e)?;
+	scheduler.schedule()?;
+	Ok(scheduler)
+}
+
+fn validate<T>(scheduler: &Scheduler<T>) -> Result<String, (i32, String)>
+where T: CliDuration
+{
+	let mut problems = dangling_dependencies(scheduler);
+	if let Some(cycle) = scheduler.find_cycle() {
+		problems.push(format!("dependency cycle: {}", cycle.join(" -> ")));
+	}
+	if problems.is_empty() {
+		Ok(format!("OK: {} tasks\n", scheduler.get_tasks().len()))
+	} else {
+		Err((EXIT_INVALID, problems.join("\n")))
+	}
+}
+
+/// Dependencies on unknown tasks, one line each.
+fn dangling_dependencies<T>(scheduler: &Scheduler<T>) -> Vec<String>
+where T: CliDuration
+{
+	scheduler.get_dangling_dependencies().iter()
+		.map(|(task, dep)| format!("task {} depends on unknown task {}", task, dep))
+		.collect()
+}
+
+/// Refuses dependencies on unknown tasks, the scheduler would silently ignore them.
+fn check_dependencies<T>(scheduler: &Scheduler<T>) -> Result<(), (i32, String)>
+where T: CliDuration
+{
+	let problems = dangling_dependencies(scheduler);
+	if problems.is_empty() {
+		Ok(())
+	} else {
+		Err((EXIT_INVALID, problems.join("\n")))
+	}
+}
+
+/// Aligned table of the timings ordered by early start.
+fn schedule_table<T>(scheduler: &Scheduler<T>) -> Result<String, String>
+where T: CliDuration
+{
+	let critical_ids = scheduler.get_critical_task_ids()?;
+	let mut tasks = scheduler.get_tasks();
+	tasks.sort_by(|a, b| a.get_early_start().partial_cmp(&b.get_early_start())
+		.unwrap_or(std::cmp::Ordering::Equal));
+	let value = |value: Option<T>| value.map_or("-".to_string(), |v| v.to_string());
+	let mut rows: Vec<Vec<String>> = vec!{
+		["ID", "DUR", "ES", "EF", "LS", "LF", "TF", "CRITICAL"].iter().map(|s| s.to_string()).collect()
+	};
+	for task in tasks {
+		rows.push(vec!{
+			task.get_id(),
+			task.get_duration().to_string(),
+			value(task.get_early_start()),
+			value(task.get_early_finish()),
+			value(task.get_late_start()),
+			value(task.get_late_finish()),
+			value(task.get_total_float().ok()),
+			if critical_ids.contains(&task.get_id()) { "*" } else { "" }.to_string(),
+		});
+	}
+	let widths: Vec<usize> = (0..rows[0].len())
+		.map(|column| rows.iter().map(|row| row[column].chars().count()).max().unwrap_or(0))
+		.collect();
+	let mut output = String::new();
+	for row in rows {
+		let cells: Vec<String> = row.iter().zip(&widths)
+			.map(|(cell, width)| format!("{:width$}", cell, width = width))
+			.collect();
+		output.push_str(cells.join("  ").trim_end());
+		output.push('\n');
+	}
+	output.push_str(&format!("Project duration: {}\n", scheduler.get_duration()?));
+	Ok(output)
+}
+
+fn export<T>(scheduler: &Scheduler<T>, format: &str) -> Result<String, (i32, String)>
+where T: CliDuration
+{
+	let output = match format {
+		"dot" => Ok(scheduler.to_dot(&dot::DotOptions::default())),
+		"json" => T::json_report(scheduler),
+		"csv" => csv::write_schedule(scheduler, ','),
+		"svg" => svg::render_gantt(scheduler, &svg::SvgOptions::default()),
+		"html" => html::render_report(scheduler, &html::HtmlOptions::default()),
+		"mermaid" => diagram::to_mermaid(scheduler, &DiagramOptions::default()),
+		"plantuml" => diagram::to_plantuml(scheduler, &DiagramOptions::default()),
+		_ => { return Err((EXIT_USAGE, format!("unknown export format: {}", format))); },
+	};
+	output.map_err(|e| (EXIT_ERROR, e))
+}
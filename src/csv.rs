@@ -0,0 +1,242 @@
+use std::fs;
+use std::str::FromStr;
+
+use crate::customtask::CustomTask;
+use crate::scheduler::Scheduler;
+
+/// Column mapping of a CSV task list.
+/// Columns are looked up by their header names.
+#[derive(Clone, Debug)]
+pub struct CsvColumns {
+	/// Header of the task ID column.
+	pub id: String,
+	/// Header of the duration column.
+	pub duration: String,
+	/// Header of the predecessor list column, optional.
+	pub predecessors: Option<String>,
+	/// Header of the task name column, stored as `name` metadata.
+	pub name: Option<String>,
+	/// Header of the resource column, stored as `resources` metadata.
+	pub resources: Option<String>,
+	/// Field delimiter.
+	pub delimiter: char,
+	/// Delimiter between the predecessor IDs inside a field.
+	pub predecessor_delimiter: char,
+}
+
+impl Default for CsvColumns {
+	fn default() -> Self {
+		CsvColumns {
+			id: "id".to_string(),
+			duration: "duration".to_string(),
+			predecessors: Some("predecessors".to_string()),
+			name: None,
+			resources: None,
+			delimiter: ',',
+			predecessor_delimiter: ';',
+		}
+	}
+}
+
+/// Header of the schedule table written by `write_schedule`.
+static SCHEDULE_HEADER: [&str; 9] = [
+	"id", "duration", "early_start", "early_finish", "late_start"
+	, "late_finish", "total_float", "free_float", "critical"
+];
+
+/// Parses a CSV task list. The first record has to be the header.
+/// All the bad rows are reported with their row and column numbers.
+pub fn parse_str<T>(contents: &str, columns: &CsvColumns) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	let records = split_records(contents, columns.delimiter)?;
+	let (_, header) = match records.first() {
+		Some(record) => record,
+		None => { return Err("Empty CSV input, header is missing.".to_string()); },
+	};
+	let find_column = |name: &String| -> Result<usize, String> {
+		header.iter().position(|column| column.trim() == name)
+			.ok_or(format!("row 1: missing column: {}", name))
+	};
+	let id_column = find_column(&columns.id)?;
+	let duration_column = find_column(&columns.duration)?;
+	let predecessor_column = columns.predecessors.as_ref().map(find_column).transpose()?;
+	let name_column = columns.name.as_ref().map(find_column).transpose()?;
+	let resource_column = columns.resources.as_ref().map(find_column).transpose()?;
+
+	let mut task_list: Vec<CustomTask<T>> = vec!{};
+	let mut errors: Vec<String> = vec!{};
+	for (row, record) in records.iter().skip(1) {
+		let field = |column: usize| -> &str {
+			record.get(column).map_or("", |value| value.trim())
+		};
+		let id = field(id_column);
+		if id.is_empty() {
+			errors.push(format!("row {}, column {}: empty task ID", row, id_column + 1));
+			continue;
+		}
+		let duration: T = match field(duration_column).parse::<T>() {
+			Ok(duration) if duration >= 0.into() => duration,
+			_ => {
+				errors.push(format!(
+					"row {}, column {}: invalid duration: '{}'"
+					, row
+					, duration_column + 1
+					, field(duration_column)
+				));
+				continue;
+			},
+		};
+		let dependencies: Vec<String> = match predecessor_column {
+			Some(column) => field(column)
+				.split(columns.predecessor_delimiter)
+				.map(|dep| dep.trim())
+				.filter(|dep| !dep.is_empty())
+				.map(|dep| dep.to_string())
+				.collect(),
+			None => vec!{},
+		};
+		let mut task = CustomTask::new(id.to_string(), duration, dependencies);
+		if let Some(column) = name_column {
+			if !field(column).is_empty() {
+				task.set_metadata("name".to_string(), field(column).to_string());
+			}
+		}
+		if let Some(column) = resource_column {
+			if !field(column).is_empty() {
+				task.set_metadata("resources".to_string(), field(column).to_string());
+			}
+		}
+		task_list.push(task);
+	}
+	if !errors.is_empty() {
+		return Err(errors.join("\n"));
+	}
+	Ok(task_list)
+}
+
+/// Reads and parses a CSV task list.
+pub fn parse_file<T>(filename: &String, columns: &CsvColumns) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	match fs::read_to_string(filename) {
+		Ok(contents) => parse_str(&contents, columns)
+			.map_err(|e| format!("{}:\n{}", filename, e)),
+		Err(e) => Err(format!("Could not read file: {}\n\r{}", filename, e)),
+	}
+}
+
+/// Writes the schedule table ordered by early start and ID.
+/// Scheduler has to be in ready state.
+pub fn write_schedule<T>(scheduler: &Scheduler<T>, delimiter: char) -> Result<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let critical_ids = scheduler.get_critical_task_ids()?;
+	let mut tasks = scheduler.get_tasks();
+	tasks.sort_by(|a, b| a.get_early_start().partial_cmp(&b.get_early_start())
+		.unwrap_or(std::cmp::Ordering::Equal));
+	let mut output = SCHEDULE_HEADER.join(&delimiter.to_string());
+	output.push('\n');
+	for task in tasks {
+		let not_calculated = || format!("Task has not been scheduled: {}", task.get_id());
+		let fields: Vec<String> = vec!{
+			task.get_id(),
+			task.get_duration().to_string(),
+			task.get_early_start().ok_or_else(not_calculated)?.to_string(),
+			task.get_early_finish().ok_or_else(not_calculated)?.to_string(),
+			task.get_late_start().ok_or_else(not_calculated)?.to_string(),
+			task.get_late_finish().ok_or_else(not_calculated)?.to_string(),
+			task.get_total_float()?.to_string(),
+			scheduler.get_free_float(task)?.to_string(),
+			critical_ids.contains(&task.get_id()).to_string(),
+		};
+		let quoted: Vec<String> = fields.iter()
+			.map(|value| quote_field(value, delimiter))
+			.collect();
+		output.push_str(&quoted.join(&delimiter.to_string()));
+		output.push('\n');
+	}
+	Ok(output)
+}
+
+/// Quotes a field if it contains a delimiter, a quote or a line break.
+fn quote_field(value: &str, delimiter: char) -> String {
+	if value.contains(delimiter) || value.contains('"') || value.contains('\n') {
+		format!("\"{}\"", value.replace('"', "\"\""))
+	} else {
+		value.to_string()
+	}
+}
+
+/// Splits the input into records of fields, handling quoted fields.
+/// Every record is returned with it's starting row number, empty lines are skipped.
+fn split_records(contents: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, String> {
+	let mut records: Vec<(usize, Vec<String>)> = vec!{};
+	let mut record: Vec<String> = vec!{};
+	let mut field = String::new();
+	let mut in_quotes = false;
+	let mut row = 1;
+	let mut record_row = 1;
+	let mut chars = contents.chars().peekable();
+	while let Some(c) = chars.next() {
+		if in_quotes {
+			match c {
+				'"' if chars.peek() == Some(&'"') => { chars.next(); field.push('"'); },
+				'"' => { in_quotes = false; },
+				'\n' => { row += 1; field.push(c); },
+				_ => { field.push(c); },
+			}
+		} else if c == '"' {
+			in_quotes = true;
+		} else if c == delimiter {
+			record.push(std::mem::take(&mut field));
+		} else if c == '\n' || c == '\r' {
+			if c == '\r' && chars.peek() == Some(&'\n') {
+				chars.next();
+			}
+			record.push(std::mem::take(&mut field));
+			if record.len() > 1 || !record[0].trim().is_empty() {
+				records.push((record_row, std::mem::take(&mut record)));
+			}
+			record.clear();
+			row += 1;
+			record_row = row;
+		} else {
+			field.push(c);
+		}
+	}
+	if in_quotes {
+		return Err(format!("row {}: unclosed quote", record_row));
+	}
+	record.push(field);
+	if record.len() > 1 || !record[0].trim().is_empty() {
+		records.push((record_row, record));
+	}
+	Ok(records)
+}
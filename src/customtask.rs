@@ -1,6 +1,9 @@
+use std::collections::BTreeMap;
 
 /// Represents a task a.k.a. a node in a batch graph.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "T: serde::Deserialize<'de>")))]
 pub struct CustomTask<T>
 where T: From<i8>
 	+ std::clone::Clone
@@ -17,15 +20,27 @@ where T: From<i8>
 	/// Duration of a task.
 	duration: T,
 	/// Earlyiest possible start for the task. (Calculated)
+	#[cfg_attr(feature = "serde", serde(default))]
 	early_start: Option<T>,
 	/// Earlyiest possible finish for the task. (Calculated)
+	#[cfg_attr(feature = "serde", serde(default))]
 	early_finish: Option<T>,
 	/// Latest possible start for the task. (Calculated)
+	#[cfg_attr(feature = "serde", serde(default))]
 	late_start: Option<T>,
 	/// Latest possible finish for the task. (Calculated)
+	#[cfg_attr(feature = "serde", serde(default))]
 	late_finish: Option<T>,
 	/// Task dependency IDs.
+	#[cfg_attr(feature = "serde", serde(default))]
 	dependencies: Vec<String>,
+	/// Optional key-value data attached to the task, not used in calculations.
+	#[cfg_attr(feature = "serde", serde(default))]
+	metadata: BTreeMap<String, String>,
+	/// How the task was written in it's input file, like it's comments.
+	/// Only read by the input file writer, not exported.
+	#[cfg_attr(feature = "serde", serde(skip))]
+	source: BTreeMap<String, String>,
 }
 
 impl <T> CustomTask<T>
@@ -48,6 +63,8 @@ where T: From<i8>
 			early_finish: None,
 			late_start: None,
 			late_finish: None,
+			metadata: BTreeMap::new(),
+			source: BTreeMap::new(),
 		}
 	}
 
@@ -79,6 +96,37 @@ where T: From<i8>
 		self.dependencies = _dependencies;
 	}
 
+	/// Gets a metadata value by it's key.
+	pub fn get_metadata(&self, key: &str) -> Option<String> {
+		self.metadata.get(key).cloned()
+	}
+
+	/// Gets all the metadata ordered by key.
+	pub fn get_metadata_map(&self) -> BTreeMap<String, String> {
+		self.metadata.clone()
+	}
+
+	pub fn set_metadata(&mut self, key: String, value: String) {
+		self.metadata.insert(key, value);
+	}
+
+	pub fn remove_metadata(&mut self, key: &str) -> Option<String> {
+		self.metadata.remove(key)
+	}
+
+	/// Gets a source value by it's key, see `input_parser::write_tasks`.
+	pub fn get_source(&self, key: &str) -> Option<String> {
+		self.source.get(key).cloned()
+	}
+
+	pub fn set_source(&mut self, key: String, value: String) {
+		self.source.insert(key, value);
+	}
+
+	pub fn remove_source(&mut self, key: &str) -> Option<String> {
+		self.source.remove(key)
+	}
+
 	pub fn get_early_start(&self) -> Option<T> {
 		self.early_start
 	}
@@ -139,6 +187,14 @@ where T: From<i8>
 		Ok(())
 	}
 
+	/// Clears the calculated values, the scheduler does it before recalculating.
+	pub fn reset_calculations(&mut self) {
+		self.early_start = None;
+		self.early_finish = None;
+		self.late_start = None;
+		self.late_finish = None;
+	}
+
 	pub fn get_total_float(&self) -> Result<T, String> {
 		let (lf, ef);
 		match self.late_finish {
@@ -165,3 +221,40 @@ where T: From<i8>
 	}
 
 }
+
+/// Serialized layout of a task. The total float is only written, it is
+/// recalculated from the late and early finish when deserializing.
+#[cfg(feature = "serde")]
+impl <T> serde::Serialize for CustomTask<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ serde::Serialize
+{
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: serde::Serializer
+	{
+		use serde::ser::SerializeStruct;
+		let mut state = serializer.serialize_struct("CustomTask", 9)?;
+		state.serialize_field("id", &self.id)?;
+		state.serialize_field("duration", &self.duration)?;
+		state.serialize_field("dependencies", &self.dependencies)?;
+		state.serialize_field("early_start", &self.early_start)?;
+		state.serialize_field("early_finish", &self.early_finish)?;
+		state.serialize_field("late_start", &self.late_start)?;
+		state.serialize_field("late_finish", &self.late_finish)?;
+		state.serialize_field("total_float", &self.get_total_float().ok())?;
+		if self.metadata.is_empty() {
+			state.skip_field("metadata")?;
+		} else {
+			state.serialize_field("metadata", &self.metadata)?;
+		}
+		state.end()
+	}
+}
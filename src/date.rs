@@ -0,0 +1,95 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A calendar date, used to map abstract time units to days.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+	year: i32,
+	month: u32,
+	day: u32,
+}
+
+impl Date {
+	pub fn new(year: i32, month: u32, day: u32) -> Result<Self, String> {
+		if month == 0 || month > 12 || day == 0 || day > days_in_month(year, month) {
+			return Err(format!("Invalid date: {:04}-{:02}-{:02}", year, month, day));
+		}
+		Ok(Date { year, month, day })
+	}
+
+	pub fn get_year(&self) -> i32 {
+		self.year
+	}
+
+	pub fn get_month(&self) -> u32 {
+		self.month
+	}
+
+	pub fn get_day(&self) -> u32 {
+		self.day
+	}
+
+	/// Gets the date the given number of days later.
+	pub fn add_days(&self, days: i64) -> Date {
+		from_day_number(self.day_number() + days)
+	}
+
+	/// Gets the number of days since 1970-01-01.
+	pub fn day_number(&self) -> i64 {
+		// Days from civil algorithm, with years starting on March 1.
+		let year = if self.month <= 2 { self.year - 1 } else { self.year } as i64;
+		let era = year.div_euclid(400);
+		let year_of_era = year - era * 400;
+		let month = self.month as i64;
+		let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5
+			+ self.day as i64 - 1;
+		let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+		era * 146097 + day_of_era - 719468
+	}
+}
+
+/// Inverse of `Date::day_number`.
+fn from_day_number(day_number: i64) -> Date {
+	let days = day_number + 719468;
+	let era = days.div_euclid(146097);
+	let day_of_era = days - era * 146097;
+	let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month_index = (5 * day_of_year + 2) / 153;
+	let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+	let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+	let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+	Date { year: year as i32, month, day }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+	match month {
+		2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
+
+/// Parses a `YYYY-MM-DD` date.
+impl FromStr for Date {
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err> {
+		let parts: Vec<&str> = text.trim().split('-').collect();
+		if parts.len() != 3 {
+			return Err(format!("Date should be in YYYY-MM-DD format: {}", text));
+		}
+		let number = |part: &str| part.parse::<u32>()
+			.map_err(|_| format!("Date should be in YYYY-MM-DD format: {}", text));
+		let year = i32::try_from(number(parts[0])?)
+			.map_err(|_| format!("Year is out of range: {}", text))?;
+		Date::new(year, number(parts[1])?, number(parts[2])?)
+	}
+}
+
+impl fmt::Display for Date {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+	}
+}
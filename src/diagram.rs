@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use crate::customtask::CustomTask;
+use crate::date::Date;
+use crate::numeric::{format_number, to_f64};
+use crate::scheduler::Scheduler;
+use crate::svg::{is_milestone, task_label};
+
+/// Settings of the Mermaid and PlantUML Gantt exports.
+#[derive(Clone, Debug, Default)]
+pub struct DiagramOptions {
+	/// Date of the project start. One time unit is mapped to one day.
+	/// Without it the charts show the abstract time units.
+	pub start_date: Option<Date>,
+	/// Title of the chart.
+	pub title: Option<String>,
+}
+
+/// Exports the schedule as a Mermaid `gantt` block.
+///
+/// Tasks with dependencies start `after` their predecessors, the start points
+/// start at their computed early start. Critical tasks are marked `crit`.
+/// Without a start date the chart uses `dateFormat X`, so one time unit is
+/// drawn as one second from zero. With a start date the start points need
+/// whole early starts, fractional durations are written as fractions of days.
+/// Scheduler has to be in ready state.
+pub fn to_mermaid<T>(scheduler: &Scheduler<T>, options: &DiagramOptions) -> Result<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let critical_ids = scheduler.get_critical_task_ids()?;
+	let tasks = tasks_by_early_start(scheduler);
+	let aliases = create_aliases(scheduler);
+
+	let mut output = String::from("gantt\n");
+	if let Some(title) = &options.title {
+		output.push_str(&format!("\ttitle {}\n", mermaid_text(title)));
+	}
+	let unit = match options.start_date {
+		Some(_) => {
+			output.push_str("\tdateFormat YYYY-MM-DD\n");
+			"d"
+		},
+		None => {
+			output.push_str("\tdateFormat X\n\taxisFormat %s\n");
+			"s"
+		},
+	};
+	for task in tasks {
+		let mut tags = String::new();
+		if critical_ids.contains(&task.get_id()) {
+			tags.push_str("crit, ");
+		}
+		if is_milestone(task) {
+			tags.push_str("milestone, ");
+		}
+		let dependencies = scheduler.get_task_dependencies(task);
+		let start = if dependencies.is_empty() {
+			let es = to_f64(task.get_early_start().unwrap_or(0.into()));
+			match options.start_date {
+				Some(_) if es.fract() != 0.0 => {
+					return Err(format!("Fractional start of task {} can not be written as a date: {}", task.get_id(), es));
+				},
+				Some(date) => date.add_days(es as i64).to_string(),
+				None => format_number(es),
+			}
+		} else {
+			let mut dep_aliases: Vec<&String> = dependencies.iter()
+				.map(|dep| &aliases[&dep.get_id()])
+				.collect();
+			dep_aliases.sort();
+			format!("after {}", dep_aliases.iter().map(|alias| alias.as_str()).collect::<Vec<&str>>().join(" "))
+		};
+		output.push_str(&format!(
+			"\t{} :{}{}, {}, {}{}\n"
+			, mermaid_text(&task_label(task))
+			, tags
+			, aliases[&task.get_id()]
+			, start
+			, format_number(to_f64(task.get_duration()))
+			, unit
+		));
+	}
+	Ok(output)
+}
+
+/// Exports the schedule as a PlantUML `@startgantt` document.
+///
+/// PlantUML counts in whole days, durations are rounded up.
+/// Dependencies are written as `starts at [...]'s end` constraints with the
+/// driving predecessor last. Critical tasks are colored red.
+/// Scheduler has to be in ready state.
+pub fn to_plantuml<T>(scheduler: &Scheduler<T>, options: &DiagramOptions) -> Result<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let critical_ids = scheduler.get_critical_task_ids()?;
+	let tasks = tasks_by_early_start(scheduler);
+	let aliases = create_aliases(scheduler);
+
+	let mut output = String::from("@startgantt\n");
+	if let Some(title) = &options.title {
+		output.push_str(&format!("title {}\n", title));
+	}
+	if let Some(date) = options.start_date {
+		output.push_str(&format!("Project starts {}\n", date));
+	}
+	for task in &tasks {
+		let label = task_label(task).replace(['[', ']'], "");
+		let alias = &aliases[&task.get_id()];
+		let mut dependencies = scheduler.get_task_dependencies(task);
+		dependencies.sort_by(|a, b| a.get_early_finish().partial_cmp(&b.get_early_finish())
+			.unwrap_or(std::cmp::Ordering::Equal));
+		if is_milestone(task) {
+			match (dependencies.last(), options.start_date) {
+				(Some(driver), _) => output.push_str(&format!(
+					"[{}] as [{}] happens at [{}]'s end\n", label, alias, aliases[&driver.get_id()]
+				)),
+				(None, Some(date)) => output.push_str(&format!(
+					"[{}] as [{}] happens {}\n", label, alias, date
+				)),
+				(None, None) => output.push_str(&format!(
+					"[{}] as [{}] happens D+0\n", label, alias
+				)),
+			}
+		} else {
+			let days = to_f64(task.get_duration()).ceil() as i64;
+			output.push_str(&format!(
+				"[{}] as [{}] requires {} day{}\n"
+				, label
+				, alias
+				, days
+				, if days == 1 { "" } else { "s" }
+			));
+			for dep in &dependencies {
+				output.push_str(&format!("[{}] starts at [{}]'s end\n", alias, aliases[&dep.get_id()]));
+			}
+		}
+		if critical_ids.contains(&task.get_id()) {
+			output.push_str(&format!("[{}] is colored in Red\n", alias));
+		}
+	}
+	output.push_str("@endgantt\n");
+	Ok(output)
+}
+
+/// Text without the characters ending a Mermaid Gantt statement.
+fn mermaid_text(text: &str) -> String {
+	text.replace([':', ';', '#', '\n', '\r'], " ")
+}
+
+fn tasks_by_early_start<T>(scheduler: &Scheduler<T>) -> Vec<&CustomTask<T>>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let mut tasks = scheduler.get_tasks();
+	tasks.sort_by(|a, b| a.get_early_start().partial_cmp(&b.get_early_start())
+		.unwrap_or(std::cmp::Ordering::Equal));
+	tasks
+}
+
+/// Creates unique aliases of word characters for the task IDs.
+fn create_aliases<T>(scheduler: &Scheduler<T>) -> HashMap<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let mut aliases: HashMap<String, String> = HashMap::new();
+	for task in scheduler.get_tasks() {
+		let base: String = task.get_id().chars()
+			.map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+			.collect();
+		let mut alias = base.clone();
+		let mut suffix = 1;
+		while aliases.values().any(|used| *used == alias) {
+			suffix += 1;
+			alias = format!("{}_{}", base, suffix);
+		}
+		aliases.insert(task.get_id(), alias);
+	}
+	aliases
+}
@@ -0,0 +1,186 @@
+use std::collections::HashSet;
+use std::fs;
+
+use crate::customtask::CustomTask;
+use crate::scheduler::Scheduler;
+
+/// Grouping of the nodes into DOT clusters.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DotCluster {
+	/// No clusters.
+	None,
+	/// One cluster for each independent component of the graph.
+	Component,
+	/// One cluster for each value of the given metadata key.
+	Metadata(String),
+}
+
+/// Settings of the Graphviz DOT export.
+#[derive(Clone, Debug)]
+pub struct DotOptions {
+	/// Lays out the graph from left to right instead of top to bottom.
+	pub left_to_right: bool,
+	/// Grouping of the nodes.
+	pub cluster: DotCluster,
+	/// Leaves out the tasks that are not on any critical path.
+	pub critical_only: bool,
+}
+
+impl Default for DotOptions {
+	fn default() -> Self {
+		DotOptions {
+			left_to_right: false,
+			cluster: DotCluster::None,
+			critical_only: false,
+		}
+	}
+}
+
+impl <T> Scheduler<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	/// Renders the task network as a Graphviz DOT graph.
+	/// Critical tasks and edges are drawn bold red. If the scheduler is not
+	/// ready, the calculated values are left empty and nothing is critical.
+	pub fn to_dot(&self, options: &DotOptions) -> String {
+		let mut critical_ids: HashSet<String> = HashSet::new();
+		let mut critical_edges: HashSet<(String, String)> = HashSet::new();
+		if self.get_duration().is_ok() {
+			for path in self.get_critical_paths() {
+				let ids = path.get_task_ids();
+				for pair in ids.windows(2) {
+					critical_edges.insert((pair[0].clone(), pair[1].clone()));
+				}
+				critical_ids.extend(ids);
+			}
+		}
+		let is_visible = |id: &String| !options.critical_only || critical_ids.contains(id);
+
+		let mut output = String::from("digraph cpm {\n");
+		if options.left_to_right {
+			output.push_str("\trankdir=LR;\n");
+		}
+		output.push_str("\tnode [shape=box, fontname=\"monospace\"];\n");
+
+		let groups: Vec<(Option<String>, Vec<String>)> = match &options.cluster {
+			DotCluster::None => vec!{(None, self.get_tasks().iter().map(|task| task.get_id()).collect())},
+			DotCluster::Component => self.get_component_ids().into_iter()
+				.enumerate()
+				.map(|(idx, ids)| (Some(format!("Component {}", idx + 1)), ids))
+				.collect(),
+			DotCluster::Metadata(key) => {
+				let mut groups: Vec<(Option<String>, Vec<String>)> = vec!{};
+				for task in self.get_tasks() {
+					let group = task.get_metadata(key);
+					match groups.iter_mut().find(|(name, _)| *name == group) {
+						Some((_, ids)) => ids.push(task.get_id()),
+						None => groups.push((group, vec!{task.get_id()})),
+					}
+				}
+				groups
+			},
+		};
+		for (idx, (group, ids)) in groups.iter().enumerate() {
+			if !ids.iter().any(is_visible) {
+				continue;
+			}
+			let indent = if group.is_some() { "\t\t" } else { "\t" };
+			if let Some(label) = group {
+				output.push_str(&format!("\tsubgraph cluster_{} {{\n", idx));
+				output.push_str(&format!("\t\tlabel={};\n", quote_id(label)));
+			}
+			for id in ids.iter().filter(|id| is_visible(id)) {
+				let task = match self.get_task_by_name(id) {
+					Some(task) => task,
+					None => { continue; },
+				};
+				let style = if critical_ids.contains(id) {
+					", color=red, penwidth=2"
+				} else {
+					""
+				};
+				output.push_str(&format!(
+					"{}{} [label={}{}];\n"
+					, indent
+					, quote_id(id)
+					, quote_id(&node_label(task))
+					, style
+				));
+			}
+			if group.is_some() {
+				output.push_str("\t}\n");
+			}
+		}
+
+		for task in self.get_tasks() {
+			if !is_visible(&task.get_id()) {
+				continue;
+			}
+			for dep in self.get_task_dependencies(task) {
+				if !is_visible(&dep.get_id()) {
+					continue;
+				}
+				let style = if critical_edges.contains(&(dep.get_id(), task.get_id())) {
+					" [color=red, penwidth=2]"
+				} else {
+					""
+				};
+				output.push_str(&format!(
+					"\t{} -> {}{};\n"
+					, quote_id(&dep.get_id())
+					, quote_id(&task.get_id())
+					, style
+				));
+			}
+		}
+		output.push_str("}\n");
+		output
+	}
+
+	/// Writes the task network into a Graphviz DOT file.
+	pub fn write_dot(&self, filename: &String, options: &DotOptions) -> Result<(), String> {
+		fs::write(filename, self.to_dot(options))
+			.map_err(|e| format!("Could not write file: {}\n\r{}", filename, e))
+	}
+}
+
+/// Label of a node: ID, duration and the calculated values.
+fn node_label<T>(task: &CustomTask<T>) -> String
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let value = |value: Option<T>| value.map_or("-".to_string(), |v| v.to_string());
+	format!(
+		"{} ({})\nES {} | EF {}\nLS {} | LF {}\nTF {}"
+		, task.get_id()
+		, task.get_duration()
+		, value(task.get_early_start())
+		, value(task.get_early_finish())
+		, value(task.get_late_start())
+		, value(task.get_late_finish())
+		, value(task.get_total_float().ok())
+	)
+}
+
+/// Quotes a DOT identifier or label.
+fn quote_id(id: &str) -> String {
+	format!(
+		"\"{}\""
+		, id.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+	)
+}
@@ -0,0 +1,133 @@
+use crate::scheduler::Scheduler;
+use crate::svg::{escape_xml, render_gantt, task_label, SvgOptions};
+
+/// Settings of the HTML report.
+#[derive(Clone, Debug)]
+pub struct HtmlOptions {
+	/// Title of the page.
+	pub title: String,
+	/// Settings of the embedded Gantt chart.
+	pub gantt: SvgOptions,
+}
+
+impl Default for HtmlOptions {
+	fn default() -> Self {
+		HtmlOptions {
+			title: "Schedule report".to_string(),
+			gantt: SvgOptions::default(),
+		}
+	}
+}
+
+static STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #ccc; padding: 4px 10px; text-align: right; }
+th { background: #f0f0f0; cursor: pointer; user-select: none; }
+td:first-child, td:nth-child(2) { text-align: left; }
+tr.critical td { color: #b52a26; font-weight: bold; }
+.bar { background: #4a7fd4; height: 12px; }
+.gantt { overflow-x: auto; }
+";
+
+/// Sorts the task table by the clicked column, numbers by their value.
+static SCRIPT: &str = "
+document.querySelectorAll('#tasks th').forEach(function (header, column) {
+	header.addEventListener('click', function () {
+		var body = document.querySelector('#tasks tbody');
+		var ascending = header.dataset.order !== 'asc';
+		header.dataset.order = ascending ? 'asc' : 'desc';
+		var rows = Array.prototype.slice.call(body.rows);
+		rows.sort(function (a, b) {
+			var x = a.cells[column].textContent, y = b.cells[column].textContent;
+			var result = (isNaN(x) || isNaN(y)) ? x.localeCompare(y) : x - y;
+			return ascending ? result : -result;
+		});
+		rows.forEach(function (row) { body.appendChild(row); });
+	});
+});
+";
+
+/// Renders a single-file HTML report of the schedule with a sortable task
+/// table, the critical paths, the concurrency profile and a Gantt chart.
+/// Scheduler has to be in ready state.
+pub fn render_report<T>(scheduler: &Scheduler<T>, options: &HtmlOptions) -> Result<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let duration = scheduler.get_duration()?;
+	let parallelism = scheduler.get_parallelism()?;
+	let critical_ids = scheduler.get_critical_task_ids()?;
+	let mut tasks = scheduler.get_tasks();
+	tasks.sort_by(|a, b| a.get_early_start().partial_cmp(&b.get_early_start())
+		.unwrap_or(std::cmp::Ordering::Equal));
+	let title = escape_xml(&options.title);
+	let value = |value: Option<T>| value.map_or("-".to_string(), |v| v.to_string());
+
+	let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+	html.push_str(&format!("<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n", title, STYLE));
+	html.push_str(&format!("<h1>{}</h1>\n", title));
+	html.push_str(&format!(
+		"<p>Project duration: <b>{}</b> &middot; Tasks: <b>{}</b> &middot; Critical tasks: <b>{}</b> &middot; Maximum parallel tasks: <b>{}</b></p>\n"
+		, duration
+		, tasks.len()
+		, critical_ids.len()
+		, parallelism
+	));
+
+	html.push_str("<h2>Tasks</h2>\n<table id=\"tasks\">\n<thead><tr>");
+	for header in ["ID", "Name", "Duration", "ES", "EF", "LS", "LF", "TF"] {
+		html.push_str(&format!("<th>{}</th>", header));
+	}
+	html.push_str("</tr></thead>\n<tbody>\n");
+	for task in &tasks {
+		let class = if critical_ids.contains(&task.get_id()) { " class=\"critical\"" } else { "" };
+		html.push_str(&format!(
+			"<tr{}><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n"
+			, class
+			, escape_xml(&task.get_id())
+			, escape_xml(&task_label(*task))
+			, task.get_duration()
+			, value(task.get_early_start())
+			, value(task.get_early_finish())
+			, value(task.get_late_start())
+			, value(task.get_late_finish())
+			, value(task.get_total_float().ok())
+		));
+	}
+	html.push_str("</tbody>\n</table>\n");
+
+	html.push_str("<h2>Critical paths</h2>\n<ol>\n");
+	for path in scheduler.get_critical_paths() {
+		let ids: Vec<String> = path.get_task_ids().iter().map(|id| escape_xml(id)).collect();
+		html.push_str(&format!("<li>{} ({})</li>\n", ids.join(" &rarr; "), path.get_dur()));
+	}
+	html.push_str("</ol>\n");
+
+	html.push_str("<h2>Concurrency profile</h2>\n<table id=\"profile\">\n");
+	html.push_str("<thead><tr><th>From</th><th>To</th><th>Tasks</th><th></th></tr></thead>\n<tbody>\n");
+	for (section_start, section_end, count) in scheduler.get_concurrency_profile()? {
+		let width = if parallelism > 0 { 200.0 * count as f64 / parallelism as f64 } else { 0.0 };
+		html.push_str(&format!(
+			"<tr><td>{}</td><td>{}</td><td>{}</td><td style=\"text-align: left\"><div class=\"bar\" style=\"width: {}px\"></div></td></tr>\n"
+			, section_start
+			, section_end
+			, count
+			, width.round()
+		));
+	}
+	html.push_str("</tbody>\n</table>\n");
+
+	html.push_str("<h2>Gantt chart</h2>\n<div class=\"gantt\">\n");
+	html.push_str(&render_gantt(scheduler, &options.gantt)?);
+	html.push_str("</div>\n");
+	html.push_str(&format!("<script>{}</script>\n</body>\n</html>\n", SCRIPT));
+	Ok(html)
+}
@@ -1,83 +1,1560 @@
+use std::collections::HashMap;
+use std::fmt;
 use std::fs;
-use regex::Regex;
+use std::io::Read;
+use std::iter::Peekable;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::{CharIndices, FromStr};
 
 use crate::customtask::CustomTask;
+use crate::scheduler::Scheduler;
+use crate::units::{UnitConfig, UnitDuration};
 
-/**
- * Pattern for task names.
+/*
+ * Grammar of the input files:
+ *
+ *   file         = { include | task } ;
+ *   include      = "include" STRING "as" ID ;
+ *   task         = ID "(" DURATION ")" [ "after" "[" [ dependencies ] "]" ] [ attributes ] ;
+ *   dependencies = ID { [ "," ] ID } [ "," ] ;
+ *   attributes   = "{" [ attribute { [ "," ] attribute } [ "," ] ] "}" ;
+ *   attribute    = KEY ":" ( item | "[" [ item { [ "," ] item } [ "," ] ] "]" ) ;
+ *   item         = WORD | STRING | WORD "=" WORD ;
+ *
+ * IDs consist of letters, digits, including Unicode ones, and `-_.`
+ * characters. Other IDs are quoted like strings, `"Design review (v2)"`.
+ * DURATION is anything the duration type parses, like `10` or `2.5`, or a
+ * number with a unit suffix converted by the `UnitConfig`, like `3d`, `4h`
+ * or `2w`.
+ * STRING is double quoted, with `\"`, `\\`, `\n` and `\t` escapes.
+ * The attribute KEYs are listed in `ATTRIBUTES`, their values are stored
+ * as task metadata.
+ *
+ * An include parses another file relative to the including one, the IDs
+ * and dependencies of it's tasks get the `ID.` namespace prefix. So
+ * `include "frontend.tasks.in" as fe` defines `fe.Build`, and the
+ * dependencies in `frontend.tasks.in` refer to it's own tasks only.
+ * Whitespace, including CRLF line endings, is allowed between any tokens.
+ * Comments are `#` or `//` until the end of the line and `/* ... */` blocks.
  */
-static TASK_NAME_PATTERN: &str
-	= r"a-zA-Z-_.0-9";
 
-/**
- * Pattern for dependency names.
- * NAME_PAT should be replaced by TASK_NAME_PATTERN.
- */
-static DEPENDENCY_NAME_PATTERN: &str
-	= r"([NAME_PAT]+)";
+/// Kinds of the input file problems.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiagnosticKind {
+	/// A word where `after` is expected, like `ater`.
+	UnknownKeyword(String),
+	/// Duration below zero.
+	NegativeDuration,
+	/// Duration the duration type can not parse.
+	InvalidDuration,
+	/// `(` or `[` without it's closing pair.
+	UnclosedBracket,
+	/// `/*` without `*/`.
+	UnclosedComment,
+	/// Task ID defined more than once.
+	DuplicateId(String),
+	/// `"` without the closing `"` on the same line.
+	UnclosedString,
+	/// Attribute key that is not in `ATTRIBUTES`.
+	UnknownAttribute(String),
+	/// Attribute with a wrong value or given more than once.
+	InvalidAttribute(String),
+	/// Included file that can not be read.
+	IncludeError,
+	/// File including itself, directly or through other files.
+	IncludeCycle,
+	/// Any other token at the wrong place.
+	UnexpectedToken,
+}
 
-/**
- * Pattern for task definition.
- * LIST_PAT should be replaced by TASK_LIST_PATTERN.
- * NAME_PAT should be replaced by TASK_NAME_PATTERN.
- */
-static TASK_DEFINITION_PATTERN: &str
-	= r"([NAME_PAT]+)\s*\(([-+]?\d+)\)(\s+after\s+\[([NAME_PAT\s,]*)\])?";
+/// A problem of the input with it's location.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+	pub kind: DiagnosticKind,
+	pub message: String,
+	/// Byte range of the problem in the source.
+	pub span: Range<usize>,
+	/// Line of the span start, counted from 1.
+	pub line: usize,
+	/// Column of the span start in characters, counted from 1.
+	pub column: usize,
+	/// Name of the parsed source, like a file name.
+	pub source_name: Option<String>,
+}
+
+impl Diagnostic {
+	fn new(source: &str, kind: DiagnosticKind, message: String, span: Range<usize>) -> Self {
+		let (line, column) = line_column(source, span.start);
+		Diagnostic { kind, message, span, line, column, source_name: None }
+	}
+
+	/// `name: line N, column M`, or without the name if it is not set.
+	pub fn location(&self) -> String {
+		match &self.source_name {
+			Some(name) => format!("{}: line {}, column {}", name, self.line, self.column),
+			None => format!("line {}, column {}", self.line, self.column),
+		}
+	}
+
+	/// Renders the message with the source line and a caret under the span.
+	pub fn render(&self, source: &str) -> String {
+		let line_text = source.lines().nth(self.line - 1).unwrap_or("").trim_end_matches('\r');
+		let line_number = self.line.to_string();
+		let gutter = " ".repeat(line_number.len());
+		let span_text = source.get(self.span.clone()).unwrap_or("");
+		let width = span_text.lines().next().unwrap_or("").chars().count().max(1);
+		format!(
+			"error: {}\n{}--> {}\n{} |\n{} | {}\n{} | {}{}\n"
+			, self.message
+			, gutter
+			, self.location()
+			, gutter
+			, line_number
+			, line_text
+			, gutter
+			, " ".repeat(self.column - 1)
+			, "^".repeat(width)
+		)
+	}
+}
+
+/// Line and character column of a byte offset, both counted from 1.
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+	let before = &source[..offset];
+	(
+		before.matches('\n').count() + 1,
+		before.rsplit('\n').next().unwrap_or("").chars().count() + 1,
+	)
+}
 
-pub fn parse_input_file(filename: &String) -> Result<Vec<CustomTask<i64>>, String> {
-	let task_def_str = TASK_DEFINITION_PATTERN.to_string()
-		.replace("NAME_PAT", TASK_NAME_PATTERN);
-	let dependency_def_str = DEPENDENCY_NAME_PATTERN.to_string()
-		.replace("NAME_PAT", TASK_NAME_PATTERN);
+/// `line N, column M: message`, prefixed by the source name if it is set.
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}: {}", self.location(), self.message)
+	}
+}
+
+/// Include directive of an input file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Include {
+	/// File name as written, relative to the including file.
+	pub path: String,
+	pub namespace: String,
+	/// Comments before and after the directive.
+	pub comment: Option<String>,
+}
+
+/// Tasks and problems of a parsed input.
+/// The tasks are the ones parsed without problems, the diagnostics are
+/// ordered by source and position.
+#[derive(Clone, Debug)]
+pub struct ParsedInput<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	pub tasks: Vec<CustomTask<T>>,
+	/// Include directives of the parsed source, without the nested ones.
+	pub includes: Vec<Include>,
+	/// Comments after the last definition of the parsed source,
+	/// or all of them if it has no definitions.
+	pub trailing_comment: Option<String>,
+	pub diagnostics: Vec<Diagnostic>,
+	/// Contents of the included files by the source names of their diagnostics.
+	pub sources: HashMap<String, String>,
+}
+
+impl <T> ParsedInput<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	/// Names the source in the diagnostics without a name,
+	/// the ones of the included files keep their names.
+	pub fn with_source_name(mut self, name: &str) -> Self {
+		for diagnostic in self.diagnostics.iter_mut() {
+			if diagnostic.source_name.is_none() {
+				diagnostic.source_name = Some(name.to_string());
+			}
+		}
+		self
+	}
+
+	/// Renders every diagnostic with the line of the file it belongs to.
+	/// `source` is the parsed contents, the included files are in `sources`.
+	pub fn render_diagnostics(&self, source: &str) -> Vec<String> {
+		self.diagnostics.iter()
+			.map(|diagnostic| {
+				let file = diagnostic.source_name.as_ref()
+					.and_then(|name| self.sources.get(name))
+					.map_or(source, |contents| contents.as_str());
+				diagnostic.render(file)
+			})
+			.collect()
+	}
 
-	let task_definition: Regex = Regex::new(task_def_str.as_str()).unwrap();
-	let dependency_definition: Regex = Regex::new(dependency_def_str.as_str()).unwrap();
+	/// The tasks if there are no problems, otherwise every diagnostic,
+	/// one per line.
+	pub fn into_result(self) -> Result<Vec<CustomTask<T>>, String> {
+		if self.diagnostics.is_empty() {
+			Ok(self.tasks)
+		} else {
+			let messages: Vec<String> = self.diagnostics.iter()
+				.map(|diagnostic| diagnostic.to_string())
+				.collect();
+			Err(messages.join("\n"))
+		}
+	}
+}
 
-	let mut task_list: Vec<CustomTask<i64>> = vec!{};
+/// Task list parsed from the input file format with `str::parse`.
+#[derive(Clone, Debug)]
+pub struct TaskList<T>(pub Vec<CustomTask<T>>)
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign;
 
-	//println!("Input file: {}", filename);
-	let contents: String;
+impl <T> FromStr for TaskList<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	type Err = String;
+
+	fn from_str(contents: &str) -> Result<Self, Self::Err> {
+		parse_input(contents).map(TaskList)
+	}
+}
+
+/// Attribute keys of the task attribute blocks with the description of
+/// their values. Each one is stored as the task metadata of the same key:
+///
+/// - `name: "Backend"` as `Backend`,
+/// - `resources: [dev=2, qa=1]` as `dev=2, qa=1`,
+/// - `tags: [api, db]` as `api, db`,
+/// - `estimate: 15..30` as `15..30`, a range of durations in the base unit,
+/// - `milestone: true` as `true`.
+///
+/// Commas and backslashes of the list items are escaped with a backslash,
+/// `tags: ["Smith, J."]` is stored as `Smith\, J.`.
+pub const ATTRIBUTES: [(&str, &str); 5] = [
+	("name", "a word or a string"),
+	("resources", "NAME=AMOUNT or a list of them"),
+	("tags", "a tag or a list of tags"),
+	("estimate", "MIN..MAX durations with MIN <= MAX"),
+	("milestone", "true or false"),
+];
+
+/// Kinds of the tokens of the input files.
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+	/// Task ID, keyword or number.
+	Word(String),
+	OpenParen,
+	CloseParen,
+	OpenBracket,
+	CloseBracket,
+	OpenBrace,
+	CloseBrace,
+	Comma,
+	Colon,
+	Equals,
+	/// Quoted string with the escapes resolved.
+	Text(String),
+	/// Any other character.
+	Unexpected(char),
+}
+
+/// Token with it's byte range in the source.
+#[derive(Clone, Debug)]
+struct Token {
+	kind: TokenKind,
+	span: Range<usize>,
+}
+
+fn is_word_char(c: char) -> bool {
+	c.is_alphanumeric() || "-_.".contains(c)
+}
+
+/// True if a `+` after `word` is the exponent sign of a number, like in `1e+5`.
+/// Elsewhere `+` ends the word.
+fn is_exponent_start(word: &str) -> bool {
+	match word.strip_suffix(['e', 'E']) {
+		Some(mantissa) => {
+			let digits = mantissa.strip_prefix('-').unwrap_or(mantissa);
+			digits.chars().any(|c| c.is_ascii_digit()) && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+		},
+		None => false,
+	}
+}
+
+/// True if `text` is read as one word token.
+fn is_word(text: &str) -> bool {
+	!text.is_empty() && text.char_indices()
+		.all(|(index, c)| is_word_char(c) || (c == '+' && is_exponent_start(&text[..index])))
+}
+
+/// Comment with it's byte range and text without the comment markers.
+struct Comment {
+	span: Range<usize>,
+	text: String,
+}
+
+/// Splits the source into tokens, skipping whitespace.
+/// Comments are collected into `comments`.
+fn tokenize(source: &str, diagnostics: &mut Vec<Diagnostic>, comments: &mut Vec<Comment>) -> Vec<Token> {
+	let mut tokens: Vec<Token> = vec!{};
+	let mut chars = source.char_indices().peekable();
+	while let Some((start, c)) = chars.next() {
+		let kind = match c {
+			_ if c.is_whitespace() => { continue; },
+			'#' => {
+				while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+				let end = chars.peek().map_or(source.len(), |(end, _)| *end);
+				comments.push(Comment { span: start..end, text: source[start + 1..end].trim().to_string() });
+				continue;
+			},
+			'/' if chars.peek().map(|(_, c)| *c) == Some('/') => {
+				while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+				let end = chars.peek().map_or(source.len(), |(end, _)| *end);
+				comments.push(Comment { span: start..end, text: source[start + 2..end].trim().to_string() });
+				continue;
+			},
+			'/' if chars.peek().map(|(_, c)| *c) == Some('*') => {
+				chars.next();
+				let mut closed = false;
+				while let Some((_, c)) = chars.next() {
+					if c == '*' && chars.next_if(|(_, c)| *c == '/').is_some() {
+						closed = true;
+						break;
+					}
+				}
+				if !closed {
+					diagnostics.push(Diagnostic::new(
+						source
+						, DiagnosticKind::UnclosedComment
+						, "unclosed block comment".to_string()
+						, start..start + 2
+					));
+				} else {
+					let end = chars.peek().map_or(source.len(), |(end, _)| *end);
+					let lines: Vec<&str> = source[start + 2..end - 2].lines().map(|line| line.trim()).collect();
+					comments.push(Comment { span: start..end, text: lines.join("\n").trim().to_string() });
+				}
+				continue;
+			},
+			'(' => TokenKind::OpenParen,
+			')' => TokenKind::CloseParen,
+			'[' => TokenKind::OpenBracket,
+			']' => TokenKind::CloseBracket,
+			'{' => TokenKind::OpenBrace,
+			'}' => TokenKind::CloseBrace,
+			',' => TokenKind::Comma,
+			':' => TokenKind::Colon,
+			'=' => TokenKind::Equals,
+			'"' => match read_string(&mut chars) {
+				Some(text) => TokenKind::Text(text),
+				None => {
+					diagnostics.push(Diagnostic::new(
+						source
+						, DiagnosticKind::UnclosedString
+						, "unclosed string".to_string()
+						, start..start + 1
+					));
+					continue;
+				},
+			},
+			_ if is_word_char(c) => {
+				let mut word = c.to_string();
+				while let Some((_, c)) = chars.next_if(|(_, c)| is_word_char(*c) || (*c == '+' && is_exponent_start(&word))) {
+					word.push(c);
+				}
+				TokenKind::Word(word)
+			},
+			_ => TokenKind::Unexpected(c),
+		};
+		let end = chars.peek().map_or(source.len(), |(end, _)| *end);
+		tokens.push(Token { kind, span: start..end });
+	}
+	tokens
+}
+
+/// Reads a quoted string after it's opening quote and resolves the escapes.
+/// `None` if the line ends before the closing quote.
+fn read_string(chars: &mut Peekable<CharIndices>) -> Option<String> {
+	let mut text = String::new();
+	while let Some((_, c)) = chars.next_if(|(_, c)| *c != '\n') {
+		match c {
+			'"' => { return Some(text); },
+			'\\' => match chars.next_if(|(_, c)| *c != '\n') {
+				Some((_, 'n')) => text.push('\n'),
+				Some((_, 't')) => text.push('\t'),
+				Some((_, c)) => text.push(c),
+				None => {},
+			},
+			_ => text.push(c),
+		}
+	}
+	None
+}
+
+/// Splits a command line at whitespace and commas. Quoted arguments are read
+/// like the strings of the input format, so they can hold any task ID.
+pub(crate) fn split_arguments(line: &str) -> Result<Vec<String>, String> {
+	let mut arguments: Vec<String> = vec!{};
+	let mut chars = line.char_indices().peekable();
+	while let Some((_, c)) = chars.next() {
+		match c {
+			_ if c.is_whitespace() || c == ',' => {},
+			'"' => arguments.push(read_string(&mut chars).ok_or("Unclosed string")?),
+			_ => {
+				let mut argument = c.to_string();
+				while let Some((_, c)) = chars.next_if(|(_, c)| !c.is_whitespace() && !",\"".contains(*c)) {
+					argument.push(c);
+				}
+				arguments.push(argument);
+			},
+		}
+	}
+	Ok(arguments)
+}
+
+/// Recursive descent parser over the tokens.
+struct Parser<'a> {
+	source: &'a str,
+	tokens: Vec<Token>,
+	next: usize,
+	/// Prefix of the task IDs and dependencies, like `fe.`.
+	namespace: String,
+	units: UnitConfig,
+}
+
+impl <'a> Parser<'a> {
+	fn peek(&self) -> Option<&TokenKind> {
+		self.peek_at(0)
+	}
+
+	fn peek_at(&self, ahead: usize) -> Option<&TokenKind> {
+		self.tokens.get(self.next + ahead).map(|token| &token.kind)
+	}
+
+	/// Span of the next token, or the empty span at the end of the source.
+	fn next_span(&self) -> Range<usize> {
+		self.tokens.get(self.next)
+			.map_or(self.source.len()..self.source.len(), |token| token.span.clone())
+	}
+
+	/// True at `ID (`, the start of a task definition.
+	fn at_task_start(&self) -> bool {
+		matches!(self.peek(), Some(TokenKind::Word(_)) | Some(TokenKind::Text(_)))
+			&& self.peek_at(1) == Some(&TokenKind::OpenParen)
+	}
+
+	/// True at `include "`, the start of an include directive.
+	fn at_include(&self) -> bool {
+		self.peek() == Some(&TokenKind::Word("include".to_string()))
+			&& matches!(self.peek_at(1), Some(TokenKind::Text(_)))
+	}
+
+	fn diagnostic(&self, kind: DiagnosticKind, message: String, span: Range<usize>) -> Diagnostic {
+		Diagnostic::new(self.source, kind, message, span)
+	}
+
+	/// Unexpected token error at the next token.
+	fn unexpected(&self, expected: &str) -> Diagnostic {
+		let found = match self.peek() {
+			Some(TokenKind::Word(word)) => format!("'{}'", word),
+			Some(TokenKind::OpenParen) => "'('".to_string(),
+			Some(TokenKind::CloseParen) => "')'".to_string(),
+			Some(TokenKind::OpenBracket) => "'['".to_string(),
+			Some(TokenKind::CloseBracket) => "']'".to_string(),
+			Some(TokenKind::OpenBrace) => "'{'".to_string(),
+			Some(TokenKind::CloseBrace) => "'}'".to_string(),
+			Some(TokenKind::Comma) => "','".to_string(),
+			Some(TokenKind::Colon) => "':'".to_string(),
+			Some(TokenKind::Equals) => "'='".to_string(),
+			Some(TokenKind::Text(text)) => format!("\"{}\"", text),
+			Some(TokenKind::Unexpected(c)) => format!("'{}'", c),
+			None => "end of input".to_string(),
+		};
+		self.diagnostic(
+			DiagnosticKind::UnexpectedToken
+			, format!("expected {}, found {}", expected, found)
+			, self.next_span()
+		)
+	}
+
+	fn expect(&mut self, kind: TokenKind, expected: &str) -> Result<(), Diagnostic> {
+		if self.peek() == Some(&kind) {
+			self.next += 1;
+			Ok(())
+		} else {
+			Err(self.unexpected(expected))
+		}
+	}
+
+	fn id(&mut self, expected: &str) -> Result<(String, Range<usize>), Diagnostic> {
+		match self.peek() {
+			Some(TokenKind::Word(id)) if is_valid_id(id) => {
+				let id = id.clone();
+				let span = self.next_span();
+				self.next += 1;
+				Ok((id, span))
+			},
+			Some(TokenKind::Text(id)) if !id.is_empty() => {
+				let id = id.clone();
+				let span = self.next_span();
+				self.next += 1;
+				Ok((id, span))
+			},
+			_ => Err(self.unexpected(expected)),
+		}
+	}
+
+	/// Skips to the next task definition or include after an error in the
+	/// one starting at the token `task_start`.
+	fn recover(&mut self, task_start: usize) {
+		self.next = self.next.max(task_start + 1);
+		while self.peek().is_some() && !self.at_task_start() && !self.at_include() {
+			self.next += 1;
+		}
+	}
+
+	/// Parses an include directive, returns the file name, the namespace
+	/// and the span of the directive.
+	fn include(&mut self) -> Result<(String, String, Range<usize>), Diagnostic> {
+		let start = self.next_span().start;
+		self.next += 1;
+		let path = match self.peek() {
+			Some(TokenKind::Text(path)) => path.clone(),
+			_ => { return Err(self.unexpected("file name after 'include'")); },
+		};
+		self.next += 1;
+		if self.peek() != Some(&TokenKind::Word("as".to_string())) {
+			return Err(self.unexpected("'as' after the included file name"));
+		}
+		self.next += 1;
+		let (namespace, span) = self.id("namespace")?;
+		Ok((path, namespace, start..span.end))
+	}
+
+	/// Parses a task, returns it with the span of it's ID.
+	fn task<T>(&mut self) -> Result<(CustomTask<T>, Range<usize>), Diagnostic>
+	where T: From<i8>
+		+ std::clone::Clone
+		+ std::marker::Copy
+		+ std::ops::Sub::<Output = T>
+		+ std::ops::Add<Output = T>
+		+ std::fmt::Display
+		+ std::fmt::Debug
+		+ std::cmp::PartialOrd
+		+ std::ops::AddAssign
+		+ FromStr
+	{
+		let (id, id_span) = self.id("task ID")?;
+		let open_paren = self.next_span();
+		self.expect(TokenKind::OpenParen, "'(' after the task ID")?;
+		// Durations with a unit keep their text for the writer.
+		let duration_text = match self.peek() {
+			Some(TokenKind::Word(word)) if word.parse::<UnitDuration>().is_ok() => Some(word.clone()),
+			_ => None,
+		};
+		let duration: T = match self.peek() {
+			Some(TokenKind::Word(word)) => match self.units.parse::<T>(word) {
+				Ok(duration) if duration >= 0.into() => duration,
+				_ if word.starts_with('-') => {
+					return Err(self.diagnostic(
+						DiagnosticKind::NegativeDuration
+						, format!("negative duration: {}", word)
+						, self.next_span()
+					));
+				},
+				_ => {
+					// A unit duration the type can not hold, like 0.5 days in integers.
+					let message = match word.parse::<UnitDuration>() {
+						Ok(duration) => format!("invalid duration: {} is {}{}", word, self.units.to_base(duration), self.units.base),
+						Err(_) => format!("invalid duration: {}", word),
+					};
+					return Err(self.diagnostic(DiagnosticKind::InvalidDuration, message, self.next_span()));
+				},
+			},
+			_ => { return Err(self.unexpected("duration")); },
+		};
+		self.next += 1;
+		if self.peek().is_none() || self.at_task_start() {
+			return Err(self.diagnostic(
+				DiagnosticKind::UnclosedBracket
+				, "unclosed '('".to_string()
+				, open_paren
+			));
+		}
+		self.expect(TokenKind::CloseParen, "')' after the duration")?;
+
+		let mut dependencies: Vec<String> = vec!{};
+		if self.peek() == Some(&TokenKind::Word("after".to_string())) {
+			self.next += 1;
+			let open_bracket = self.next_span();
+			self.expect(TokenKind::OpenBracket, "'[' after 'after'")?;
+			while self.peek() != Some(&TokenKind::CloseBracket) {
+				if self.peek().is_none() || self.at_task_start() {
+					return Err(self.diagnostic(
+						DiagnosticKind::UnclosedBracket
+						, "unclosed '['".to_string()
+						, open_bracket
+					));
+				}
+				let (dependency, _) = self.id("dependency ID or ']'")?;
+				dependencies.push(format!("{}{}", self.namespace, dependency));
+				if self.peek() == Some(&TokenKind::Comma) {
+					self.next += 1;
+				}
+			}
+			self.next += 1;
+		} else if let Some(TokenKind::Word(word)) = self.peek() {
+			// A word followed by a list is a misspelled keyword, not the next task.
+			if self.peek_at(1) == Some(&TokenKind::OpenBracket) {
+				return Err(self.diagnostic(
+					DiagnosticKind::UnknownKeyword(word.clone())
+					, format!("unknown keyword '{}', expected 'after'", word)
+					, self.next_span()
+				));
+			}
+		}
+		let mut task = CustomTask::new(format!("{}{}", self.namespace, id), duration, dependencies);
+		if let Some(duration_text) = duration_text {
+			task.set_source("duration".to_string(), duration_text);
+		}
+		if self.peek() == Some(&TokenKind::OpenBrace) {
+			self.attributes(&mut task)?;
+		}
+		Ok((task, id_span))
+	}
+
+	/// Parses an attribute block into the metadata of the task.
+	fn attributes<T>(&mut self, task: &mut CustomTask<T>) -> Result<(), Diagnostic>
+	where T: From<i8>
+		+ std::clone::Clone
+		+ std::marker::Copy
+		+ std::ops::Sub::<Output = T>
+		+ std::ops::Add<Output = T>
+		+ std::fmt::Display
+		+ std::fmt::Debug
+		+ std::cmp::PartialOrd
+		+ std::ops::AddAssign
+		+ FromStr
+	{
+		let open_brace = self.next_span();
+		self.next += 1;
+		let mut seen: Vec<String> = vec!{};
+		while self.peek() != Some(&TokenKind::CloseBrace) {
+			if self.peek().is_none() || self.at_task_start() {
+				return Err(self.diagnostic(
+					DiagnosticKind::UnclosedBracket
+					, "unclosed '{'".to_string()
+					, open_brace
+				));
+			}
+			let key_span = self.next_span();
+			let key = match self.peek() {
+				Some(TokenKind::Word(word)) => word.clone(),
+				_ => { return Err(self.unexpected("attribute name or '}'")); },
+			};
+			let expected = match ATTRIBUTES.iter().find(|(name, _)| *name == key) {
+				Some((_, expected)) => *expected,
+				None => {
+					let names: Vec<&str> = ATTRIBUTES.iter().map(|(name, _)| *name).collect();
+					return Err(self.diagnostic(
+						DiagnosticKind::UnknownAttribute(key.clone())
+						, format!("unknown attribute '{}', expected one of: {}", key, names.join(", "))
+						, key_span
+					));
+				},
+			};
+			if seen.contains(&key) {
+				return Err(self.diagnostic(
+					DiagnosticKind::InvalidAttribute(key.clone())
+					, format!("attribute '{}' is given more than once", key)
+					, key_span
+				));
+			}
+			self.next += 1;
+			self.expect(TokenKind::Colon, "':' after the attribute name")?;
+			let value_start = self.next_span().start;
+			let value = self.value()?;
+			let value_span = value_start..self.tokens[self.next - 1].span.end;
+			let estimate_text = match &value {
+				Value::Text(range) if key == "estimate"
+					&& range.split("..").any(|end| end.parse::<UnitDuration>().is_ok()) => Some(range.clone()),
+				_ => None,
+			};
+			if let Some(estimate_text) = estimate_text {
+				task.set_source("estimate".to_string(), estimate_text);
+			}
+			match attribute_text::<T>(&key, value, &self.units) {
+				Some(text) => task.set_metadata(key.clone(), text),
+				None => {
+					return Err(self.diagnostic(
+						DiagnosticKind::InvalidAttribute(key.clone())
+						, format!("invalid value of '{}', expected {}", key, expected)
+						, value_span
+					));
+				},
+			}
+			seen.push(key);
+			if self.peek() == Some(&TokenKind::Comma) {
+				self.next += 1;
+			}
+		}
+		self.next += 1;
+		Ok(())
+	}
+
+	/// Parses an attribute value, a single item or a list of them.
+	fn value(&mut self) -> Result<Value, Diagnostic> {
+		if self.peek() != Some(&TokenKind::OpenBracket) {
+			return self.item();
+		}
+		let open_bracket = self.next_span();
+		self.next += 1;
+		let mut items: Vec<Value> = vec!{};
+		while self.peek() != Some(&TokenKind::CloseBracket) {
+			if self.peek().is_none() || self.peek() == Some(&TokenKind::CloseBrace) || self.at_task_start() {
+				return Err(self.diagnostic(
+					DiagnosticKind::UnclosedBracket
+					, "unclosed '['".to_string()
+					, open_bracket
+				));
+			}
+			items.push(self.item()?);
+			if self.peek() == Some(&TokenKind::Comma) {
+				self.next += 1;
+			}
+		}
+		self.next += 1;
+		Ok(Value::List(items))
+	}
+
+	fn item(&mut self) -> Result<Value, Diagnostic> {
+		let value = match self.peek() {
+			Some(TokenKind::Word(word)) if !self.at_task_start() => Value::Text(word.clone()),
+			Some(TokenKind::Text(text)) => Value::Text(text.clone()),
+			_ => { return Err(self.unexpected("attribute value")); },
+		};
+		self.next += 1;
+		match (value, self.peek()) {
+			(Value::Text(name), Some(TokenKind::Equals)) => {
+				self.next += 1;
+				match self.peek() {
+					Some(TokenKind::Word(amount)) => {
+						let amount = amount.clone();
+						self.next += 1;
+						Ok(Value::Pair(name, amount))
+					},
+					_ => Err(self.unexpected("amount after '='")),
+				}
+			},
+			(value, _) => Ok(value),
+		}
+	}
+}
+
+/// Parsed attribute value.
+enum Value {
+	/// Word or string.
+	Text(String),
+	/// `NAME=AMOUNT`.
+	Pair(String, String),
+	List(Vec<Value>),
+}
+
+/// Metadata text of an attribute value, `None` if the value does not fit
+/// the attribute.
+fn attribute_text<T>(key: &str, value: Value, units: &UnitConfig) -> Option<String>
+where T: From<i8>
+	+ std::cmp::PartialOrd
+	+ std::fmt::Display
+	+ FromStr
+{
+	let items = match value {
+		Value::List(items) => items,
+		value => vec!{value},
+	};
+	match key {
+		"name" | "estimate" | "milestone" if items.len() != 1 => None,
+		"name" => match &items[0] {
+			Value::Text(name) => Some(name.clone()),
+			_ => None,
+		},
+		"tags" => items.into_iter()
+			.map(|item| match item {
+				Value::Text(tag) => Some(tag),
+				_ => None,
+			})
+			.collect::<Option<Vec<String>>>()
+			.map(|tags| join_items(&tags)),
+		"resources" => items.into_iter()
+			.map(|item| match item {
+				Value::Pair(name, amount) if amount.parse::<f64>().is_ok_and(|amount| amount >= 0.0) => {
+					Some(format!("{}={}", name, amount))
+				},
+				_ => None,
+			})
+			.collect::<Option<Vec<String>>>()
+			.map(|resources| join_items(&resources)),
+		"estimate" => match &items[0] {
+			Value::Text(range) => {
+				let (min, max) = range.split_once("..")?;
+				let min = units.parse::<T>(min).ok()?;
+				let max = units.parse::<T>(max).ok()?;
+				if min >= 0.into() && min <= max {
+					Some(format!("{}..{}", min, max))
+				} else {
+					None
+				}
+			},
+			_ => None,
+		},
+		"milestone" => match &items[0] {
+			Value::Text(flag) if flag == "true" || flag == "false" => Some(flag.clone()),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// True if the ID is valid without quotes.
+pub(crate) fn is_valid_id(word: &str) -> bool {
+	!word.is_empty() && word.chars().all(|c| c.is_alphanumeric() || "-_.".contains(c))
+}
+
+/// Writes an ID, quoted only if needed. `after` is quoted too, after a task
+/// it would be read as the keyword.
+fn write_id(id: &str) -> String {
+	if is_valid_id(id) && id != "after" { id.to_string() } else { quote(id) }
+}
+
+/// Reads and parses an input file, durations are parsed into `T`.
+pub fn parse_input_file<T>(filename: &String) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
 	match fs::read_to_string(filename) {
-		Ok(file_content) => {contents = file_content;},
-		Err(e) => { return Err(format!("Could not read file: {}\n\r{}", filename, e)); },
+		Ok(contents) => parse_file_diagnostics(filename, &contents).into_result(),
+		Err(e) => Err(format!("Could not read file: {}\n\r{}", filename, e)),
+	}
+}
+
+/// Parses task definitions from a string.
+/// `source_name` names the source in the error messages, like a file name.
+/// Includes are relative to the current directory.
+pub fn parse_str<T>(source_name: &str, contents: &str) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	parse_input_diagnostics(contents).with_source_name(source_name).into_result()
+}
+
+/// Reads the whole reader, like the standard input, and parses it.
+/// `source_name` names the source in the error messages.
+pub fn parse_reader<T, R>(source_name: &str, mut reader: R) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr,
+	R: Read
+{
+	let mut contents = String::new();
+	reader.read_to_string(&mut contents)
+		.map_err(|e| format!("Could not read {}: {}", source_name, e))?;
+	parse_str(source_name, &contents)
+}
+
+/// Parses task definitions from the content of an input file.
+/// The error lists every problem, one per line, starting with it's line
+/// and column. See `parse_input_diagnostics` for the details.
+pub fn parse_input<T>(contents: &str) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	parse_input_diagnostics(contents).into_result()
+}
+
+/// Parses task definitions and collects every problem instead of stopping
+/// at the first one. After an error the parser continues at the next task
+/// definition, the tasks with problems are left out.
+pub fn parse_input_diagnostics<T>(contents: &str) -> ParsedInput<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	parse_input_with(contents, None, &ParseOptions::default())
+}
+
+/// Parses the contents of the file `filename`, the includes are relative to
+/// it's directory and the diagnostics are named after it.
+pub fn parse_file_diagnostics<T>(filename: &str, contents: &str) -> ParsedInput<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	parse_input_with(contents, Some(filename), &ParseOptions::default())
+}
+
+/// Options of the parser.
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+	/// Conversion of the durations with units.
+	pub units: UnitConfig,
+	/// False to only record the include directives in `includes`,
+	/// without reading the files, like for formatting.
+	pub follow_includes: bool,
+}
+
+impl Default for ParseOptions {
+	fn default() -> Self {
+		ParseOptions {
+			units: UnitConfig::default(),
+			follow_includes: true,
+		}
+	}
+}
+
+/// General form of `parse_input_diagnostics` and `parse_file_diagnostics`.
+/// With a `filename` the includes are relative to it and the diagnostics are
+/// named after it, otherwise the includes are relative to the current directory.
+pub fn parse_input_with<T>(contents: &str, filename: Option<&str>, options: &ParseOptions) -> ParsedInput<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	let mut loader = Loader::new(options.clone());
+	let base_dir = match filename {
+		Some(filename) => {
+			let path = Path::new(filename);
+			if let Ok(canonical) = fs::canonicalize(path) {
+				loader.stack.push((canonical, filename.to_string()));
+			}
+			path.parent().unwrap_or(Path::new(""))
+		},
+		None => Path::new(""),
 	};
+	loader.parse(contents, filename, base_dir, "");
+	loader.finish()
+}
 
-	let mut leftover = contents.clone();
+/// Parses a source and the files it includes into one task list.
+struct Loader<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	parsed: ParsedInput<T>,
+	/// Location of the first definitions of the task IDs.
+	defined_at: HashMap<String, String>,
+	/// Canonical paths and names of the files being parsed, for the cycle detection.
+	stack: Vec<(PathBuf, String)>,
+	/// Source names in the order of parsing, the diagnostics are sorted by it.
+	order: Vec<Option<String>>,
+	options: ParseOptions,
+}
 
-	for cap in task_definition.captures_iter(&contents) {
-		let id = cap[1].to_string();
-		let duration: u32;
-		match cap[2].parse::<u32>() {
-			Ok(dur) => duration = dur,
-			Err(_) => {
-				return Err(find_pattern_error(&cap[2], contents.clone()));
+/// Comments of a definition ending at `end`: the ones since the previous
+/// definition, and the one after it on it's last line. Moves `previous_end`
+/// after them.
+fn attached_comments(
+	source: &str,
+	comments: &[Comment],
+	previous_end: &mut usize,
+	end: usize,
+) -> (Option<String>, Option<String>) {
+	let before: Vec<&str> = comments.iter()
+		.filter(|comment| comment.span.start >= *previous_end && comment.span.start < end)
+		.map(|comment| comment.text.as_str())
+		.collect();
+	let line_comment = comments.iter()
+		.find(|comment| comment.span.start >= end && !source[end..comment.span.start].contains('\n'));
+	*previous_end = line_comment.map_or(end, |comment| comment.span.end);
+	(
+		if before.is_empty() { None } else { Some(before.join("\n")) },
+		line_comment.map(|comment| comment.text.clone()),
+	)
+}
+
+impl <T> Loader<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	fn new(options: ParseOptions) -> Self {
+		Loader {
+			parsed: ParsedInput {
+				tasks: vec!{},
+				includes: vec!{},
+				trailing_comment: None,
+				diagnostics: vec!{},
+				sources: HashMap::new(),
 			},
+			options,
+			defined_at: HashMap::new(),
+			stack: vec!{},
+			order: vec!{},
 		}
+	}
 
-		let dependency_str = cap.get(4).map_or("", |m| m.as_str());
-		let mut dependencies: Vec<String> = vec!{};
-		for dep_name in dependency_definition.captures_iter(&dependency_str) {
-			dependencies.push(dep_name[1].to_string());
+	/// Parses the contents of a source. After an error the parser continues
+	/// at the next task definition, the tasks with problems are left out.
+	fn parse(&mut self, contents: &str, source_name: Option<&str>, base_dir: &Path, namespace: &str) {
+		self.order.push(source_name.map(|name| name.to_string()));
+		let mut diagnostics: Vec<Diagnostic> = vec!{};
+		let mut comments: Vec<Comment> = vec!{};
+		let mut parser = Parser {
+			source: contents,
+			tokens: tokenize(contents, &mut diagnostics, &mut comments),
+			next: 0,
+			namespace: namespace.to_string(),
+			units: self.options.units,
+		};
+		// End of the previous definition with it's line comment.
+		let mut previous_end = 0;
+		while parser.peek().is_some() {
+			let task_start = parser.next;
+			if parser.at_include() {
+				match parser.include() {
+					Ok((path, include_namespace, span)) => {
+						let (comment, line_comment) = attached_comments(contents, &comments, &mut previous_end, span.end);
+						if namespace.is_empty() {
+							let comment: Vec<String> = comment.into_iter().chain(line_comment).collect();
+							self.parsed.includes.push(Include {
+								path: path.clone(),
+								namespace: include_namespace.clone(),
+								comment: if comment.is_empty() { None } else { Some(comment.join("\n")) },
+							});
+						}
+						if self.options.follow_includes {
+							let namespace = format!("{}{}.", namespace, include_namespace);
+							if let Err((kind, message)) = self.include(base_dir.join(path), &namespace) {
+								diagnostics.push(parser.diagnostic(kind, message, span));
+							}
+						}
+					},
+					Err(diagnostic) => {
+						diagnostics.push(diagnostic);
+						parser.recover(task_start);
+						previous_end = parser.tokens[parser.next - 1].span.end;
+					},
+				}
+				continue;
+			}
+			match parser.task::<T>() {
+				Ok((mut task, id_span)) => {
+					let end = parser.tokens[parser.next - 1].span.end;
+					let (comment, line_comment) = attached_comments(contents, &comments, &mut previous_end, end);
+					if let Some(comment) = comment {
+						task.set_source("comment".to_string(), comment);
+					}
+					if let Some(line_comment) = line_comment {
+						task.set_source("line_comment".to_string(), line_comment);
+					}
+					let (line, column) = line_column(contents, id_span.start);
+					match self.defined_at.get(&task.get_id()) {
+						Some(location) => diagnostics.push(parser.diagnostic(
+							DiagnosticKind::DuplicateId(task.get_id())
+							, format!("duplicate task ID: {}, first defined at {}", task.get_id(), location)
+							, id_span
+						)),
+						None => {
+							let location = match source_name {
+								Some(name) => format!("{}: line {}, column {}", name, line, column),
+								None => format!("line {}, column {}", line, column),
+							};
+							self.defined_at.insert(task.get_id(), location);
+							self.parsed.tasks.push(task);
+						},
+					}
+				},
+				Err(diagnostic) => {
+					diagnostics.push(diagnostic);
+					parser.recover(task_start);
+					previous_end = parser.tokens[parser.next - 1].span.end;
+				},
+			}
+		}
+		if namespace.is_empty() {
+			let trailing: Vec<&str> = comments.iter()
+				.filter(|comment| comment.span.start >= previous_end)
+				.map(|comment| comment.text.as_str())
+				.collect();
+			if !trailing.is_empty() {
+				self.parsed.trailing_comment = Some(trailing.join("\n"));
+			}
+		}
+		for diagnostic in diagnostics.iter_mut() {
+			diagnostic.source_name = source_name.map(|name| name.to_string());
+		}
+		self.parsed.diagnostics.extend(diagnostics);
+	}
+
+	/// Parses an included file with the namespace prefix of it's tasks.
+	fn include(&mut self, path: PathBuf, namespace: &str) -> Result<(), (DiagnosticKind, String)> {
+		let name = path.display().to_string();
+		let read = fs::canonicalize(&path)
+			.and_then(|canonical| fs::read_to_string(&canonical).map(|contents| (canonical, contents)));
+		let (canonical, contents) = read.map_err(|e| (
+			DiagnosticKind::IncludeError
+			, format!("could not read included file {}: {}", name, e)
+		))?;
+		if let Some(position) = self.stack.iter().position(|(path, _)| *path == canonical) {
+			let mut cycle: Vec<String> = self.stack[position..].iter()
+				.map(|(_, name)| name.clone())
+				.collect();
+			cycle.push(name);
+			return Err((DiagnosticKind::IncludeCycle, format!("include cycle: {}", cycle.join(" -> "))));
+		}
+		self.stack.push((canonical, name.clone()));
+		self.parse(&contents, Some(&name), path.parent().unwrap_or(Path::new("")), namespace);
+		self.stack.pop();
+		self.parsed.sources.insert(name, contents);
+		Ok(())
+	}
+
+	fn finish(mut self) -> ParsedInput<T> {
+		let order = self.order;
+		self.parsed.diagnostics.sort_by_key(|diagnostic| (
+			order.iter().position(|name| *name == diagnostic.source_name),
+			diagnostic.span.start,
+		));
+		self.parsed
+	}
+}
+
+/// Order of the tasks in the written input files.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TaskOrder {
+	/// The order of the given tasks, like the order they were added.
+	Insertion,
+	/// Ordered by ID.
+	Alphabetical,
+	/// Dependencies before the dependent tasks, otherwise in insertion order.
+	Topological,
+}
+
+/// `insertion`, `alphabetical` or `topological`.
+impl FromStr for TaskOrder {
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err> {
+		match text {
+			"insertion" => Ok(TaskOrder::Insertion),
+			"alphabetical" => Ok(TaskOrder::Alphabetical),
+			"topological" => Ok(TaskOrder::Topological),
+			_ => Err(format!("unknown task order: {}, expected insertion, alphabetical or topological", text)),
+		}
+	}
+}
+
+/// Options of the input file writer.
+#[derive(Clone, Debug)]
+pub struct WriteOptions {
+	pub order: TaskOrder,
+	/// Tasks longer than this, without the comments, get their dependencies
+	/// and attributes one per line. 0 turns the wrapping off.
+	pub width: usize,
+	/// Write the `comment` and `line_comment` metadata as comments.
+	pub comments: bool,
+	/// Units the tasks were parsed with. The durations and estimates are
+	/// written as in the source, like `3d`, while they still match.
+	pub units: UnitConfig,
+}
+
+impl Default for WriteOptions {
+	fn default() -> Self {
+		WriteOptions {
+			order: TaskOrder::Insertion,
+			width: 80,
+			comments: true,
+			units: UnitConfig::default(),
+		}
+	}
+}
+
+/// Writes tasks in the input file format, one task per line, in the given order.
+/// See `write_tasks` for the details.
+pub fn write_input<T>(tasks: &[&CustomTask<T>]) -> String
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	write_tasks(tasks, &WriteOptions { width: 0, ..WriteOptions::default() })
+}
+
+/// Writes tasks in the input file format. Parsing the output gives the
+/// same tasks. Metadata is written as attributes if it's key is in
+/// `ATTRIBUTES` and it's value can be read back, the `comment` and
+/// `line_comment` sources are written as `#` comments and other metadata is
+/// left out.
+pub fn write_tasks<T>(tasks: &[&CustomTask<T>], options: &WriteOptions) -> String
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let mut output = String::new();
+	for (index, task) in ordered_tasks(tasks, options.order).iter().enumerate() {
+		let comment = task.get_source("comment").filter(|_| options.comments);
+		if let Some(comment) = comment {
+			if index > 0 {
+				output.push('\n');
+			}
+			output.push_str(&write_comment(&comment));
+		}
+		output.push_str(&write_task(task, options.width, &options.units));
+		if let Some(line_comment) = task.get_source("line_comment").filter(|_| options.comments) {
+			output.push_str(&format!("  # {}", line_comment));
+		}
+		output.push('\n');
+	}
+	output
+}
+
+/// Writes the tasks of a scheduler in the input file format.
+/// The insertion order is the order the tasks were added to the scheduler.
+pub fn write_scheduler<T>(scheduler: &Scheduler<T>, options: &WriteOptions) -> String
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	write_tasks(&scheduler.get_tasks_in_insertion_order(), options)
+}
+
+/// Writes a parsed input back with it's include directives first and
+/// the trailing comments last, like the canonical form of a hand-written file.
+pub fn write_parsed<T>(parsed: &ParsedInput<T>, options: &WriteOptions) -> String
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let mut output = String::new();
+	for include in &parsed.includes {
+		if let Some(comment) = include.comment.as_ref().filter(|_| options.comments) {
+			output.push_str(&write_comment(comment));
+		}
+		output.push_str(&format!("include {} as {}\n", quote(&include.path), write_id(&include.namespace)));
+	}
+	if !parsed.includes.is_empty() && !parsed.tasks.is_empty() {
+		output.push('\n');
+	}
+	let tasks: Vec<&CustomTask<T>> = parsed.tasks.iter().collect();
+	output.push_str(&write_tasks(&tasks, options));
+	if let Some(comment) = parsed.trailing_comment.as_ref().filter(|_| options.comments) {
+		if !output.is_empty() {
+			output.push('\n');
+		}
+		output.push_str(&write_comment(comment));
+	}
+	output
+}
+
+fn ordered_tasks<'a, T>(tasks: &[&'a CustomTask<T>], order: TaskOrder) -> Vec<&'a CustomTask<T>>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let mut ordered: Vec<&CustomTask<T>> = tasks.to_vec();
+	match order {
+		TaskOrder::Insertion => {},
+		TaskOrder::Alphabetical => ordered.sort_by_key(|task| task.get_id()),
+		TaskOrder::Topological => {
+			// Repeatedly takes the first task with all it's listed dependencies
+			// written. Tasks in a cycle keep their order at the end.
+			let ids: Vec<String> = tasks.iter().map(|task| task.get_id()).collect();
+			let mut written: Vec<String> = vec!{};
+			let mut remaining = ordered;
+			ordered = vec!{};
+			while let Some(position) = remaining.iter().position(|task| {
+				task.get_dependencies().iter().all(|dep| !ids.contains(dep) || written.contains(dep))
+			}) {
+				let task = remaining.remove(position);
+				written.push(task.get_id());
+				ordered.push(task);
+			}
+			ordered.extend(remaining);
+		},
+	}
+	ordered
+}
+
+fn write_comment(comment: &str) -> String {
+	comment.lines()
+		.map(|line| if line.is_empty() { "#\n".to_string() } else { format!("# {}\n", line) })
+		.collect()
+}
+
+/// One task, wrapped if it is longer than `width`.
+fn write_task<T>(task: &CustomTask<T>, width: usize, units: &UnitConfig) -> String
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let duration = task.get_duration().to_string();
+	let duration = source_text(task.get_source("duration"), &duration, units).unwrap_or(duration);
+	let head = format!("{}({})", write_id(&task.get_id()), duration);
+	let dependencies: Vec<String> = task.get_dependencies().iter().map(|dependency| write_id(dependency)).collect();
+	let attributes: Vec<String> = ATTRIBUTES.iter()
+		.filter_map(|(key, _)| {
+			let value = task.get_metadata(key)?;
+			let value = match *key {
+				"estimate" => source_text(task.get_source("estimate"), &value, units).unwrap_or(value),
+				_ => value,
+			};
+			write_attribute(key, &value).map(|value| format!("{}: {}", key, value))
+		})
+		.collect();
+	let mut line = head.clone();
+	if !dependencies.is_empty() {
+		line.push_str(&format!(" after [{}]", dependencies.join(", ")));
+	}
+	if !attributes.is_empty() {
+		line.push_str(&format!(" {{ {} }}", attributes.join(", ")));
+	}
+	if width == 0 || line.chars().count() <= width {
+		return line;
+	}
+	let mut wrapped = head;
+	if !dependencies.is_empty() {
+		wrapped.push_str(" after [\n");
+		for dependency in &dependencies {
+			wrapped.push_str(&format!("  {},\n", dependency));
+		}
+		wrapped.push(']');
+	}
+	if !attributes.is_empty() {
+		wrapped.push_str(" {\n");
+		for attribute in &attributes {
+			wrapped.push_str(&format!("  {},\n", attribute));
 		}
+		wrapped.push('}');
+	}
+	wrapped
+}
 
-		let task = CustomTask::new(id, duration.into(), dependencies);
-		//println!("Task: {:?}", task);
-		leftover = leftover.replacen(&cap[0], "", 1);
-		task_list.push(task);
+/// Duration or `MIN..MAX` range as written in the source, if it still has
+/// the value of the task. Changed durations are written as numbers.
+fn source_text(text: Option<String>, value: &str, units: &UnitConfig) -> Option<String> {
+	let text = text?;
+	let ends: Vec<&str> = text.split("..").collect();
+	let values: Vec<&str> = value.split("..").collect();
+	let same = ends.len() == values.len() && ends.iter().zip(&values).all(|(end, value)| {
+		match (units.parse::<f64>(end), value.parse::<f64>()) {
+			(Ok(end), Ok(value)) => (end - value).abs() <= 1e-9 * value.abs().max(1.0),
+			_ => false,
+		}
+	});
+	if same { Some(text) } else { None }
+}
+
+/// Metadata text of the items of a list attribute, see `ATTRIBUTES`.
+fn join_items(items: &[String]) -> String {
+	items.iter()
+		.map(|item| item.replace('\\', "\\\\").replace(',', "\\,"))
+		.collect::<Vec<String>>()
+		.join(", ")
+}
+
+/// Items of a list attribute from it's metadata text.
+fn split_items(text: &str) -> Vec<String> {
+	let mut items: Vec<String> = vec!{};
+	let mut item = String::new();
+	let mut chars = text.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			'\\' => item.extend(chars.next()),
+			',' => {
+				items.push(std::mem::take(&mut item));
+				chars = chars.as_str().strip_prefix(' ').unwrap_or(chars.as_str()).chars();
+			},
+			c => item.push(c),
+		}
 	}
-	if leftover.replace("\n", "").len() > 0 {
-		return Err(find_pattern_error(&leftover, contents.clone()));
+	items.push(item);
+	items
+}
+
+/// Attribute value text of a metadata value, `None` if it would not parse
+/// back to the same metadata.
+fn write_attribute(key: &str, value: &str) -> Option<String> {
+	match key {
+		"name" => Some(quote(value)),
+		"tags" => {
+			let tags: Vec<String> = split_items(value).iter()
+				.map(|tag| if is_word(tag) { tag.to_string() } else { quote(tag) })
+				.collect();
+			Some(format!("[{}]", tags.join(", ")))
+		},
+		"resources" => split_items(value).iter()
+			.map(|resource| {
+				let (name, amount) = resource.rsplit_once('=').filter(|(_, amount)| is_word(amount))?;
+				let name = if is_word(name) { name.to_string() } else { quote(name) };
+				Some(format!("{}={}", name, amount))
+			})
+			.collect::<Option<Vec<String>>>()
+			.map(|resources| format!("[{}]", resources.join(", "))),
+		"estimate" if value.contains("..") && is_word(value) => Some(value.to_string()),
+		"milestone" if value == "true" || value == "false" => Some(value.to_string()),
+		_ => None,
+	}
+}
+
+fn quote(text: &str) -> String {
+	let mut quoted = String::from("\"");
+	for c in text.chars() {
+		match c {
+			'"' => quoted.push_str("\\\""),
+			'\\' => quoted.push_str("\\\\"),
+			'\n' => quoted.push_str("\\n"),
+			'\t' => quoted.push_str("\\t"),
+			_ => quoted.push(c),
+		}
 	}
-	Ok(task_list)
+	quoted.push('"');
+	quoted
 }
 
+#[deprecated(since = "0.1.8", note = "use `parse_input_diagnostics`, it reports exact positions")]
 pub fn find_pattern_error(error_str: &str, contents: String) -> String {
 	let output: String;
 	let error_lines: Vec<&str> = error_str.split('\n').collect();
 	for error_line in error_lines {
 		if error_line.len() > 0 {
 			//println!("Error line: {}", error_line);
-			let pos = contents.find(error_line).unwrap();
+			let pos = match contents.find(error_line) {
+				Some(pos) => pos,
+				None => { break; },
+			};
 			let (left_split, _) = contents.split_at(pos);
 			let lines = left_split.split('\n').collect::<Vec<&str>>();
 			let line_num = lines.len();
@@ -88,4 +1565,3 @@ pub fn find_pattern_error(error_str: &str, contents: String) -> String {
 	}
 	return format!("failed to parse errorous lines.");
 }
-
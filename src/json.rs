@@ -0,0 +1,154 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+
+use crate::customtask::CustomTask;
+use crate::project::ProjectFile;
+use crate::scheduler::Scheduler;
+use crate::summary::CriticalPath;
+
+/// Scheduled result of a single task.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaskReport<T> {
+	pub id: String,
+	pub duration: T,
+	pub dependencies: Vec<String>,
+	pub early_start: T,
+	pub early_finish: T,
+	pub late_start: T,
+	pub late_finish: T,
+	pub total_float: T,
+	pub free_float: T,
+	/// True if the task is part of a critical path.
+	pub critical: bool,
+}
+
+/// Scheduled result of a whole project.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Report<T> {
+	/// Duration of the project, the latest early finish.
+	pub duration: T,
+	/// Maximum number of parallel tasks at a time.
+	pub parallelism: u32,
+	/// Tasks ordered by early start and ID.
+	pub tasks: Vec<TaskReport<T>>,
+	pub critical_paths: Vec<CriticalPath<T>>,
+}
+
+/// Parses a JSON project definition.
+pub fn parse_str<T>(contents: &str) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ DeserializeOwned
+{
+	let project: ProjectFile<T> = serde_json::from_str(contents)
+		.map_err(|e| format!("Invalid JSON project: {}", e))?;
+	project.into_tasks()
+}
+
+/// Reads and parses a JSON project file.
+pub fn parse_file<T>(filename: &String) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ DeserializeOwned
+{
+	match fs::read_to_string(filename) {
+		Ok(contents) => parse_str(&contents)
+			.map_err(|e| format!("{}: {}", filename, e)),
+		Err(e) => Err(format!("Could not read file: {}\n\r{}", filename, e)),
+	}
+}
+
+/// Writes the tasks of a scheduler as a JSON project definition.
+pub fn write_project<T>(scheduler: &Scheduler<T>) -> Result<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ Serialize
+{
+	serde_json::to_string_pretty(&ProjectFile::from_scheduler(scheduler))
+		.map_err(|e| format!("Could not write JSON project: {}", e))
+}
+
+/// Collects the scheduled results.
+/// Scheduler has to be in ready state.
+pub fn create_report<T>(scheduler: &Scheduler<T>) -> Result<Report<T>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let duration = scheduler.get_duration()?;
+	let critical_ids = scheduler.get_critical_task_ids()?;
+	let mut tasks: Vec<TaskReport<T>> = vec!{};
+	for task in scheduler.get_tasks() {
+		let not_calculated = || format!("Task has not been scheduled: {}", task.get_id());
+		tasks.push(TaskReport {
+			id: task.get_id(),
+			duration: task.get_duration(),
+			dependencies: task.get_dependencies(),
+			early_start: task.get_early_start().ok_or_else(not_calculated)?,
+			early_finish: task.get_early_finish().ok_or_else(not_calculated)?,
+			late_start: task.get_late_start().ok_or_else(not_calculated)?,
+			late_finish: task.get_late_finish().ok_or_else(not_calculated)?,
+			total_float: task.get_total_float()?,
+			free_float: scheduler.get_free_float(task)?,
+			critical: critical_ids.contains(&task.get_id()),
+		});
+	}
+	// Stable sort keeps the ID order between equal early starts.
+	tasks.sort_by(|a, b| a.early_start.partial_cmp(&b.early_start)
+		.unwrap_or(std::cmp::Ordering::Equal));
+	let critical_paths = scheduler.get_critical_paths().iter()
+		.map(CriticalPath::from_path)
+		.collect();
+	Ok(Report {
+		duration,
+		parallelism: scheduler.get_parallelism()?,
+		tasks,
+		critical_paths,
+	})
+}
+
+/// Writes the scheduled results as JSON.
+/// Scheduler has to be in ready state.
+pub fn write_report<T>(scheduler: &Scheduler<T>) -> Result<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ Serialize
+{
+	serde_json::to_string_pretty(&create_report(scheduler)?)
+		.map_err(|e| format!("Could not write JSON report: {}", e))
+}
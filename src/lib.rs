@@ -12,7 +12,48 @@ pub mod scheduler;
 
 /// Input file parser.
 pub mod input_parser;
+/// SVG Gantt chart renderer.
+pub mod svg;
+/// Terminal Gantt chart renderer.
+pub mod terminal;
+/// Structured schedule summary and it's formatters.
+pub mod summary;
+/// Full-screen terminal Gantt viewer.
+#[cfg(feature = "tui")]
+pub mod tui;
+/// Standalone HTML schedule report.
+pub mod html;
+/// Mermaid and PlantUML Gantt export.
+pub mod diagram;
+/// Calendar dates for the chart exports.
+pub mod date;
+/// Duration units (hours, days, weeks) of the input files.
+pub mod units;
+/// Graphviz DOT export.
+pub mod dot;
+/// CSV task lists and schedule tables.
+pub mod csv;
+/// Watching input files and comparing schedules.
+pub mod watch;
+/// Interactive what-if shell.
+pub mod repl;
+/// Microsoft Project XML (MSPDI) import and export.
+#[cfg(feature = "mspdi")]
+pub mod mspdi;
+/// Common schema of the structured project files.
+#[cfg(feature = "serde")]
+pub mod project;
+/// JSON project files and reports.
+#[cfg(feature = "json")]
+pub mod json;
+/// YAML project files.
+#[cfg(feature = "yaml")]
+pub mod yaml;
+/// TOML project files.
+#[cfg(feature = "toml")]
+pub mod toml;
 
+mod numeric;
 mod tests;
 
 pub use crate::customtask::CustomTask;
@@ -0,0 +1,400 @@
+use std::collections::HashSet;
+use std::fs;
+use std::str::FromStr;
+use roxmltree::{Document, Node};
+
+use crate::customtask::CustomTask;
+use crate::date::Date;
+use crate::scheduler::Scheduler;
+use crate::svg::escape_xml;
+
+/// Link types of MSPDI predecessor links, indexed by their type number.
+static LINK_TYPES: [&str; 4] = ["FF", "FS", "SF", "SS"];
+
+/// Working hours of a day in the default MSPDI calendar.
+const HOURS_PER_DAY: f64 = 8.0;
+
+/// Conversion settings between MSPDI durations and task durations.
+#[derive(Clone, Debug)]
+pub struct MspdiOptions {
+	/// Working hours in one task duration unit.
+	pub hours_per_unit: f64,
+	/// Project start date written on export, e.g. `2024-01-01T08:00:00`.
+	pub start_date: Option<String>,
+	/// Project name written on export.
+	pub project_name: Option<String>,
+}
+
+impl Default for MspdiOptions {
+	fn default() -> Self {
+		MspdiOptions {
+			hours_per_unit: 8.0,
+			start_date: None,
+			project_name: None,
+		}
+	}
+}
+
+/// Parses a Microsoft Project XML (MSPDI) document.
+///
+/// The task UID becomes the task ID, the task name and the milestone flag
+/// are stored as `name` and `milestone` metadata. Summary tasks are skipped.
+/// The scheduler only knows finish-to-start links without lag, other link
+/// types and lags are kept in the `predecessor_links` metadata
+/// (e.g. `2:SS+4h`) and written back on export.
+pub fn parse_str<T>(contents: &str, options: &MspdiOptions) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	let document = Document::parse(contents)
+		.map_err(|e| format!("Invalid MSPDI document: {}", e))?;
+	// Links to summary tasks can not be scheduled as task dependencies.
+	let summary_uids: HashSet<&str> = document.descendants()
+		.filter(|node| node.has_tag_name("Task") && child_text(*node, "Summary") == Some("1"))
+		.filter_map(|node| child_text(node, "UID"))
+		.collect();
+	let mut task_list: Vec<CustomTask<T>> = vec!{};
+	let task_nodes = document.descendants()
+		.filter(|node| node.has_tag_name("Task"));
+	for node in task_nodes {
+		let position = document.text_pos_at(node.range().start);
+		let uid = match child_text(node, "UID") {
+			Some(uid) => uid.to_string(),
+			None => {
+				return Err(format!("Task without UID at {}", position));
+			},
+		};
+		if summary_uids.contains(uid.as_str()) {
+			continue;
+		}
+		let hours = match child_text(node, "Duration") {
+			Some(duration) => parse_duration(duration).ok_or(
+				format!("Invalid duration of task {} at {}: {}", uid, position, duration)
+			)?,
+			None => 0.0,
+		};
+		let duration: T = hours_to_duration(hours, options).ok_or(format!(
+			"Duration of task {} is not representable: {} units"
+			, uid
+			, hours / options.hours_per_unit
+		))?;
+
+		let mut dependencies: Vec<String> = vec!{};
+		let mut links: Vec<String> = vec!{};
+		let link_nodes = node.children()
+			.filter(|child| child.has_tag_name("PredecessorLink"));
+		for link in link_nodes {
+			let predecessor = match child_text(link, "PredecessorUID") {
+				Some(predecessor) => predecessor.to_string(),
+				None => {
+					return Err(format!("Predecessor link without UID in task {}", uid));
+				},
+			};
+			if summary_uids.contains(predecessor.as_str()) {
+				continue;
+			}
+			let link_type = child_text(link, "Type")
+				.and_then(|link_type| link_type.parse::<usize>().ok())
+				.and_then(|link_type| LINK_TYPES.get(link_type))
+				.unwrap_or(&"FS");
+			let lag_hours = child_text(link, "LinkLag")
+				.and_then(|lag| lag.parse::<f64>().ok())
+				.map_or(0.0, |lag| lag / 600.0);
+			if *link_type != "FS" || lag_hours != 0.0 {
+				warn!(
+					"Task {} has a {} link with {}h lag to {}, it is scheduled as finish-to-start."
+					, uid, link_type, lag_hours, predecessor
+				);
+			}
+			links.push(format_link(&predecessor, link_type, lag_hours));
+			dependencies.push(predecessor);
+		}
+
+		let mut task = CustomTask::new(uid, duration, dependencies);
+		if let Some(name) = child_text(node, "Name") {
+			task.set_metadata("name".to_string(), name.to_string());
+		}
+		if child_text(node, "Milestone") == Some("1") {
+			task.set_metadata("milestone".to_string(), "true".to_string());
+		}
+		if links.iter().any(|link| !link.ends_with(":FS")) {
+			task.set_metadata("predecessor_links".to_string(), links.join(", "));
+		}
+		task_list.push(task);
+	}
+	Ok(task_list)
+}
+
+/// Reads and parses a Microsoft Project XML (MSPDI) file.
+pub fn parse_file<T>(filename: &String, options: &MspdiOptions) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	match fs::read_to_string(filename) {
+		Ok(contents) => parse_str(&contents, options)
+			.map_err(|e| format!("{}: {}", filename, e)),
+		Err(e) => Err(format!("Could not read file: {}\n\r{}", filename, e)),
+	}
+}
+
+/// Writes the tasks of a scheduler as a Microsoft Project XML (MSPDI) document.
+///
+/// Numeric task IDs are kept as UIDs, other tasks get the next free UID.
+/// If the scheduler is ready, the critical flag and the slacks are written too,
+/// with a start date also the early start and finish as `Start` and `Finish`.
+/// Those count days of 8 working hours from the start time, skipping weekends.
+pub fn write_project<T>(scheduler: &Scheduler<T>, options: &MspdiOptions) -> Result<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let tasks = scheduler.get_tasks();
+	let critical_ids = scheduler.get_critical_task_ids().ok();
+	let project_start = match &options.start_date {
+		Some(start_date) => Some(parse_start_date(start_date)?),
+		None => None,
+	};
+
+	let mut used_uids: HashSet<u32> = tasks.iter()
+		.filter_map(|task| task.get_id().parse::<u32>().ok())
+		.filter(|uid| *uid > 0)
+		.collect();
+	let mut uids: Vec<(String, u32)> = vec!{};
+	let mut next_uid = 1;
+	for task in &tasks {
+		let uid = match task.get_id().parse::<u32>() {
+			Ok(uid) if uid > 0 => uid,
+			_ => {
+				while used_uids.contains(&next_uid) {
+					next_uid += 1;
+				}
+				used_uids.insert(next_uid);
+				next_uid
+			},
+		};
+		uids.push((task.get_id(), uid));
+	}
+	let uid_of = |id: &String| uids.iter().find(|(task_id, _)| task_id == id).map(|(_, uid)| *uid);
+
+	let mut output = String::new();
+	output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n");
+	output.push_str("<Project xmlns=\"http://schemas.microsoft.com/project\">\n");
+	if let Some(name) = &options.project_name {
+		output.push_str(&format!("\t<Name>{}</Name>\n", escape_xml(name)));
+	}
+	output.push_str("\t<ScheduleFromStart>1</ScheduleFromStart>\n");
+	if let Some(start_date) = &options.start_date {
+		output.push_str(&format!("\t<StartDate>{}</StartDate>\n", escape_xml(start_date)));
+	}
+	output.push_str("\t<Tasks>\n");
+	for (idx, task) in tasks.iter().enumerate() {
+		let uid = uid_of(&task.get_id()).unwrap_or_default();
+		let hours = duration_to_hours(task.get_duration(), options)?;
+		let name = task.get_metadata("name").unwrap_or(task.get_id());
+		let milestone = hours == 0.0
+			|| task.get_metadata("milestone").as_deref() == Some("true");
+		output.push_str("\t\t<Task>\n");
+		output.push_str(&format!("\t\t\t<UID>{}</UID>\n", uid));
+		output.push_str(&format!("\t\t\t<ID>{}</ID>\n", idx + 1));
+		output.push_str(&format!("\t\t\t<Name>{}</Name>\n", escape_xml(&name)));
+		if let (Some(project_start), Some(_)) = (&project_start, &critical_ids) {
+			let not_calculated = || format!("Task has not been scheduled: {}", task.get_id());
+			let es = duration_to_hours(task.get_early_start().ok_or_else(not_calculated)?, options)?;
+			let ef = duration_to_hours(task.get_early_finish().ok_or_else(not_calculated)?, options)?;
+			output.push_str(&format!("\t\t\t<Start>{}</Start>\n", working_time(project_start, es, false)));
+			output.push_str(&format!("\t\t\t<Finish>{}</Finish>\n", working_time(project_start, ef, ef > es)));
+		}
+		output.push_str(&format!("\t\t\t<Duration>{}</Duration>\n", format_duration(hours)));
+		output.push_str("\t\t\t<DurationFormat>7</DurationFormat>\n");
+		output.push_str(&format!("\t\t\t<Milestone>{}</Milestone>\n", milestone as u8));
+		output.push_str("\t\t\t<Summary>0</Summary>\n");
+		if let Some(critical_ids) = &critical_ids {
+			output.push_str(&format!(
+				"\t\t\t<Critical>{}</Critical>\n"
+				, critical_ids.contains(&task.get_id()) as u8
+			));
+			let total_slack = duration_to_hours(task.get_total_float()?, options)?;
+			let free_slack = duration_to_hours(scheduler.get_free_float(task)?, options)?;
+			output.push_str(&format!("\t\t\t<TotalSlack>{}</TotalSlack>\n", to_tenth_minutes(total_slack)));
+			output.push_str(&format!("\t\t\t<FreeSlack>{}</FreeSlack>\n", to_tenth_minutes(free_slack)));
+		}
+		let links = task.get_metadata("predecessor_links").unwrap_or_default();
+		for dep in task.get_dependencies() {
+			let dep_uid = match uid_of(&dep) {
+				Some(dep_uid) => dep_uid,
+				None => { continue; },
+			};
+			let (link_type, lag_hours) = links.split(", ")
+				.filter_map(parse_link)
+				.find(|(predecessor, _, _)| *predecessor == dep)
+				.map_or((1, 0.0), |(_, link_type, lag_hours)| (link_type, lag_hours));
+			output.push_str("\t\t\t<PredecessorLink>\n");
+			output.push_str(&format!("\t\t\t\t<PredecessorUID>{}</PredecessorUID>\n", dep_uid));
+			output.push_str(&format!("\t\t\t\t<Type>{}</Type>\n", link_type));
+			output.push_str(&format!("\t\t\t\t<LinkLag>{}</LinkLag>\n", to_tenth_minutes(lag_hours)));
+			output.push_str("\t\t\t\t<LagFormat>5</LagFormat>\n");
+			output.push_str("\t\t\t</PredecessorLink>\n");
+		}
+		output.push_str("\t\t</Task>\n");
+	}
+	output.push_str("\t</Tasks>\n");
+	output.push_str("</Project>\n");
+	Ok(output)
+}
+
+/// Gets the text of the first child element with the given name.
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+	node.children()
+		.find(|child| child.has_tag_name(name))
+		.and_then(|child| child.text())
+		.map(|text| text.trim())
+}
+
+/// Parses an ISO 8601 duration like `PT8H30M0S` into hours.
+fn parse_duration(duration: &str) -> Option<f64> {
+	let mut rest = duration.strip_prefix('P')?;
+	let mut hours = 0.0;
+	let mut in_time = false;
+	while !rest.is_empty() {
+		if let Some(time) = rest.strip_prefix('T') {
+			in_time = true;
+			rest = time;
+			continue;
+		}
+		let unit_pos = rest.find(|c: char| c.is_ascii_alphabetic())?;
+		let value = rest[..unit_pos].parse::<f64>().ok()?;
+		hours += match (&rest[unit_pos..unit_pos + 1], in_time) {
+			("D", false) => value * 24.0,
+			("W", false) => value * 24.0 * 7.0,
+			("H", true) => value,
+			("M", true) => value / 60.0,
+			("S", true) => value / 3600.0,
+			_ => { return None; },
+		};
+		rest = &rest[unit_pos + 1..];
+	}
+	Some(hours)
+}
+
+/// Formats hours as an ISO 8601 duration like `PT8H30M0S`.
+fn format_duration(hours: f64) -> String {
+	let total_seconds = (hours * 3600.0).round() as u64;
+	format!(
+		"PT{}H{}M{}S"
+		, total_seconds / 3600
+		, total_seconds % 3600 / 60
+		, total_seconds % 60
+	)
+}
+
+/// Converts hours into tenths of minutes, the MSPDI unit of lags and slacks.
+fn to_tenth_minutes(hours: f64) -> i64 {
+	(hours * 600.0).round() as i64
+}
+
+fn hours_to_duration<T: FromStr>(hours: f64, options: &MspdiOptions) -> Option<T> {
+	format!("{}", hours / options.hours_per_unit).parse::<T>().ok()
+}
+
+fn duration_to_hours<T: std::fmt::Display>(duration: T, options: &MspdiOptions)
+-> Result<f64, String> {
+	match duration.to_string().parse::<f64>() {
+		Ok(value) => Ok(value * options.hours_per_unit),
+		Err(_) => Err(format!("Duration is not a number: {}", duration)),
+	}
+}
+
+/// Formats a predecessor link like `2:SS+4h`.
+fn format_link(predecessor: &str, link_type: &str, lag_hours: f64) -> String {
+	if lag_hours == 0.0 {
+		format!("{}:{}", predecessor, link_type)
+	} else {
+		format!("{}:{}{:+}h", predecessor, link_type, lag_hours)
+	}
+}
+
+/// Parses a predecessor link like `2:SS+4h` into the predecessor,
+/// the MSPDI link type number and the lag in hours.
+fn parse_link(link: &str) -> Option<(String, usize, f64)> {
+	let (predecessor, rest) = link.rsplit_once(':')?;
+	let link_type = LINK_TYPES.iter().position(|link_type| rest.starts_with(link_type))?;
+	let lag = &rest[2..];
+	let lag_hours = if lag.is_empty() {
+		0.0
+	} else {
+		lag.strip_suffix('h')?.parse::<f64>().ok()?
+	};
+	Some((predecessor.to_string(), link_type, lag_hours))
+}
+
+/// Parses a project start like `2024-01-01T08:00:00` into the date and the
+/// start time in hours. Without a time the day starts at 8 o'clock.
+fn parse_start_date(start_date: &str) -> Result<(Date, f64), String> {
+	let error = || format!("Start date should be in YYYY-MM-DDTHH:MM:SS format: {}", start_date);
+	let (date, time) = start_date.trim().split_once('T').unwrap_or((start_date.trim(), "08:00:00"));
+	let date = date.parse::<Date>().map_err(|_| error())?;
+	let mut hours = 0.0;
+	for (idx, part) in time.split(':').enumerate() {
+		let value = part.parse::<u32>().ok().filter(|_| idx < 3).ok_or_else(error)?;
+		hours += value as f64 / 60f64.powi(idx as i32);
+	}
+	Ok((date, hours))
+}
+
+/// Converts working hours since the project start into an MSPDI date and time.
+/// A finish at the end of a working day stays on that day instead of
+/// moving to the start of the next one, milestones finish when they start.
+fn working_time(project_start: &(Date, f64), hours: f64, finish: bool) -> String {
+	let (mut date, start_time) = *project_start;
+	let mut days = (hours / HOURS_PER_DAY).floor();
+	let mut rest = hours - days * HOURS_PER_DAY;
+	if finish && rest == 0.0 && days > 0.0 {
+		days -= 1.0;
+		rest = HOURS_PER_DAY;
+	}
+	while is_weekend(date) {
+		date = date.add_days(1);
+	}
+	let mut remaining = days as i64;
+	while remaining > 0 {
+		date = date.add_days(1);
+		if !is_weekend(date) {
+			remaining -= 1;
+		}
+	}
+	let seconds = ((start_time + rest) * 3600.0).round() as i64;
+	format!(
+		"{}T{:02}:{:02}:{:02}"
+		, date
+		, seconds / 3600
+		, seconds % 3600 / 60
+		, seconds % 60
+	)
+}
+
+fn is_weekend(date: Date) -> bool {
+	// 1970-01-01 was a Thursday.
+	(date.day_number() + 3).rem_euclid(7) >= 5
+}
@@ -0,0 +1,33 @@
+use std::fmt::Display;
+
+/// Converts a duration value to `f64` through it's textual form,
+/// so it works for any numeric duration type. Non-numeric values are 0.
+pub(crate) fn to_f64<T: Display>(value: T) -> f64 {
+	value.to_string().parse::<f64>().unwrap_or(0.0)
+}
+
+/// Formats a number without trailing zeros and float noise.
+pub(crate) fn format_number(value: f64) -> String {
+	let rounded = (value * 1000.0).round() / 1000.0;
+	if rounded == 0.0 {
+		"0".to_string()
+	} else {
+		format!("{}", rounded)
+	}
+}
+
+/// Gets a round step (1, 2 or 5 times a power of ten) that splits the
+/// range into at most `max_ticks` sections.
+pub(crate) fn tick_step(range: f64, max_ticks: u32) -> f64 {
+	if range <= 0.0 {
+		return 1.0;
+	}
+	let raw_step = range / max_ticks as f64;
+	let magnitude = 10f64.powf(raw_step.log10().floor());
+	for multiplier in [1.0, 2.0, 5.0] {
+		if magnitude * multiplier >= raw_step {
+			return magnitude * multiplier;
+		}
+	}
+	magnitude * 10.0
+}
@@ -3,6 +3,7 @@ use crate::customtask::CustomTask;
 /// Represents a path of tasks.
 /// It is a copy of an original path in the graph.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Path<T>
 where T: From<i8>
 	+ std::clone::Clone
@@ -82,6 +83,11 @@ where T: From<i8>
 		path_string
 	}
 
+	/// Gets the IDs of the tasks in the order of the path.
+	pub fn get_task_ids(&self) -> Vec<String> {
+		self.tasks.iter().map(|task| task.get_id()).collect()
+	}
+
 	/// Gets the duration of the path.
 	pub fn get_dur(&self) -> T {
 		let mut dur = 0.into();
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+use crate::customtask::CustomTask;
+use crate::scheduler::Scheduler;
+
+/// Serializable project definition, the common schema of the
+/// structured input formats.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectFile<T> {
+	/// Task definitions.
+	pub tasks: Vec<ProjectTask<T>>,
+}
+
+/// A single task definition of a project file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectTask<T> {
+	/// Identifier of the task.
+	pub id: String,
+	/// Duration of the task.
+	pub duration: T,
+	/// Dependency IDs, optional.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub dependencies: Vec<String>,
+	/// Key-value data of the task, optional.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub metadata: BTreeMap<String, String>,
+}
+
+impl <T> ProjectFile<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	/// Creates a project from a list of tasks, keeping their order.
+	pub fn from_tasks(tasks: &[CustomTask<T>]) -> Self {
+		ProjectFile {
+			tasks: tasks.iter().map(|task| ProjectTask {
+				id: task.get_id(),
+				duration: task.get_duration(),
+				dependencies: task.get_dependencies(),
+				metadata: task.get_metadata_map(),
+			}).collect(),
+		}
+	}
+
+	/// Creates a project from the tasks of a scheduler, ordered by ID.
+	pub fn from_scheduler(scheduler: &Scheduler<T>) -> Self {
+		let tasks: Vec<CustomTask<T>> = scheduler.get_tasks().into_iter()
+			.cloned()
+			.collect();
+		ProjectFile::from_tasks(&tasks)
+	}
+
+	/// Converts the project into a task list for `Scheduler::fill_tasklist`.
+	/// Fails on negative durations.
+	pub fn into_tasks(self) -> Result<Vec<CustomTask<T>>, String> {
+		let mut task_list: Vec<CustomTask<T>> = vec!{};
+		for project_task in self.tasks {
+			if project_task.duration < 0.into() {
+				return Err(format!(
+					"Negative duration in task {}: {}"
+					, project_task.id
+					, project_task.duration
+				));
+			}
+			let mut task = CustomTask::new(
+				project_task.id
+				, project_task.duration
+				, project_task.dependencies
+			);
+			for (key, value) in project_task.metadata {
+				task.set_metadata(key, value);
+			}
+			task_list.push(task);
+		}
+		Ok(task_list)
+	}
+}
@@ -0,0 +1,333 @@
+use std::fs;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use crate::customtask::CustomTask;
+use crate::input_parser::{split_arguments, write_scheduler, WriteOptions};
+use crate::scheduler::Scheduler;
+use crate::units::{UnitConfig, UnitDuration};
+use crate::watch::{ScheduleDiff, ScheduleSnapshot};
+
+static HELP: &str = "Commands:
+    set ID DURATION             Change the duration of a task, like 5, 3d or 4h.
+    add ID DURATION [after ID...]
+                                Add a task, dependencies are separated by spaces or commas.
+    rm ID                       Remove a task and the dependencies on it.
+    critical                    Print the critical paths.
+    float ID                    Print the total and free float of a task.
+    duration                    Print the project duration.
+    undo                        Revert the last change.
+    save [FILE]                 Write the tasks to FILE or to the loaded file.
+    help                        Print this help.
+    quit                        Leave.
+IDs with other characters than letters, digits, '-', '_' and '.' are quoted,
+like \"Design review (v2)\".
+";
+
+/// Interactive what-if shell on top of a scheduler.
+///
+/// Every change is made on the scheduler and recalculated right away, then
+/// answered with the difference to the previous schedule. Only the edited
+/// tasks are kept for `undo`. Changes that can not be scheduled, like the
+/// ones making a dependency cycle, are refused.
+pub struct Repl<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	scheduler: Scheduler<T>,
+	/// Undo steps of the changes, the last one is the most recent.
+	history: Vec<Undo<T>>,
+	/// File of the `save` command without a file name.
+	path: Option<String>,
+	/// Conversion of the durations with units, like `set B 3d`.
+	units: UnitConfig,
+}
+
+impl <T> Repl<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	/// Schedules the tasks. `path` is the file the tasks were loaded from.
+	pub fn new(tasks: Vec<CustomTask<T>>, path: Option<String>) -> Result<Self, String> {
+		Ok(Repl {
+			scheduler: schedule(tasks)?,
+			history: vec!{},
+			path,
+			units: UnitConfig::default(),
+		})
+	}
+
+	/// Sets the conversion of the durations with units.
+	pub fn set_units(&mut self, units: UnitConfig) {
+		self.units = units;
+	}
+
+	/// The current, calculated schedule.
+	pub fn get_scheduler(&self) -> &Scheduler<T> {
+		&self.scheduler
+	}
+
+	/// Executes a command line and returns it's output.
+	/// IDs can be quoted like in the input files: `set "Design review (v2)" 3d`.
+	pub fn execute(&mut self, line: &str) -> Result<String, String> {
+		let arguments = split_arguments(line)?;
+		let words: Vec<&str> = arguments.iter().map(|argument| argument.as_str()).collect();
+		match words.as_slice() {
+			[] => Ok(String::new()),
+			["help"] => Ok(HELP.to_string()),
+			["set", id, duration_text] => {
+				let duration = parse_duration::<T>(duration_text, &self.units)?;
+				let id = id.to_string();
+				self.change(|scheduler| {
+					match scheduler.get_mut_task_by_name(&id) {
+						Some(task) => {
+							let previous = task.clone();
+							task.set_duration(duration);
+							match duration_source(duration_text) {
+								Some(text) => task.set_source("duration".to_string(), text),
+								None => { task.remove_source("duration"); },
+							}
+							Ok(Undo::Restore(vec!{previous}))
+						},
+						None => Err(format!("Unknown task: {}", id)),
+					}
+				})
+			},
+			["add", id, duration_text, rest @ ..] => {
+				let duration = parse_duration::<T>(duration_text, &self.units)?;
+				let dependencies: Vec<String> = match rest {
+					[] => vec!{},
+					["after", ids @ ..] if !ids.is_empty() => ids.iter().map(|dep| dep.to_string()).collect(),
+					_ => { return Err("Usage: add ID DURATION [after ID...]".to_string()); },
+				};
+				if id.is_empty() {
+					return Err("Invalid task ID: empty".to_string());
+				}
+				let mut task = CustomTask::new(id.to_string(), duration, dependencies);
+				if let Some(text) = duration_source(duration_text) {
+					task.set_source("duration".to_string(), text);
+				}
+				self.change(|scheduler| {
+					for dep in task.get_dependencies() {
+						if scheduler.get_task_by_name(&dep).is_none() {
+							return Err(format!("Unknown task: {}", dep));
+						}
+					}
+					let id = task.get_id();
+					scheduler.add_task(task)?;
+					Ok(Undo::Remove(id))
+				})
+			},
+			["rm", id] => {
+				let id = id.to_string();
+				self.change(|scheduler| {
+					let index = scheduler.get_tasks_in_insertion_order().iter()
+						.position(|task| task.get_id() == id);
+					let removed = scheduler.remove_task(&id)?;
+					let dependents: Vec<CustomTask<T>> = scheduler.get_tasks().into_iter()
+						.filter(|task| task.get_dependencies().contains(&id))
+						.cloned()
+						.collect();
+					for dependent in &dependents {
+						if let Some(task) = scheduler.get_mut_task_by_name(&dependent.get_id()) {
+							let dependencies = task.get_dependencies().into_iter()
+								.filter(|dep| *dep != id)
+								.collect();
+							task.set_dependencies(dependencies);
+						}
+					}
+					Ok(Undo::Insert(index.unwrap_or_default(), removed, dependents))
+				})
+			},
+			["critical"] => {
+				let mut paths: Vec<String> = self.scheduler.get_critical_paths().iter()
+					.map(|path| format!("{} ({})\n", path.get_task_ids().join(" -> "), path.get_dur()))
+					.collect();
+				paths.sort();
+				Ok(paths.concat())
+			},
+			["float", id] => {
+				let task = self.scheduler.get_task_by_name(&id.to_string())
+					.ok_or(format!("Unknown task: {}", id))?;
+				Ok(format!(
+					"Total float: {}\nFree float: {}\n"
+					, task.get_total_float()?
+					, self.scheduler.get_free_float(task)?
+				))
+			},
+			["duration"] => Ok(format!("Project duration: {}\n", self.scheduler.get_duration()?)),
+			["undo"] => {
+				let undo = self.history.pop().ok_or("Nothing to undo.")?;
+				let before = ScheduleSnapshot::of(&self.scheduler)?;
+				undo.apply(&mut self.scheduler)?;
+				self.scheduler.calculate()?;
+				Ok(ScheduleDiff::between_snapshots(&before, &ScheduleSnapshot::of(&self.scheduler)?).to_string())
+			},
+			["save"] => {
+				let path = self.path.clone().ok_or("No file to save to, use: save FILE")?;
+				self.save(&path)
+			},
+			["save", path] => {
+				let output = self.save(path)?;
+				self.path = Some(path.to_string());
+				Ok(output)
+			},
+			[command, ..] => Err(format!("Unknown command or wrong arguments: {}, try help", command)),
+		}
+	}
+
+	/// Reads commands until `quit` or the end of the input.
+	/// Prompts and outputs are written to `output`, errors do not stop the loop.
+	pub fn run<R, W>(&mut self, input: R, output: &mut W) -> Result<(), String>
+	where R: BufRead,
+		W: Write
+	{
+		let write_error = |e: std::io::Error| format!("Could not write output: {}", e);
+		let mut lines = input.lines();
+		loop {
+			write!(output, "> ").map_err(write_error)?;
+			output.flush().map_err(write_error)?;
+			let line = match lines.next() {
+				Some(line) => line.map_err(|e| format!("Could not read input: {}", e))?,
+				None => { break; },
+			};
+			let line = line.trim();
+			if line == "quit" || line == "exit" {
+				break;
+			}
+			match self.execute(line) {
+				Ok(text) => write!(output, "{}", text),
+				Err(e) => writeln!(output, "Error: {}", e),
+			}.map_err(write_error)?;
+		}
+		Ok(())
+	}
+
+	/// Applies a change on the scheduler and recalculates it. `edit` checks
+	/// it's arguments before changing anything and returns how to undo the
+	/// change. A change that can not be calculated is undone.
+	fn change<F>(&mut self, edit: F) -> Result<String, String>
+	where F: FnOnce(&mut Scheduler<T>) -> Result<Undo<T>, String>
+	{
+		let before = ScheduleSnapshot::of(&self.scheduler)?;
+		let undo = edit(&mut self.scheduler)?;
+		if let Err(e) = self.scheduler.calculate() {
+			undo.apply(&mut self.scheduler)?;
+			self.scheduler.calculate()?;
+			return Err(e);
+		}
+		self.history.push(undo);
+		Ok(ScheduleDiff::between_snapshots(&before, &ScheduleSnapshot::of(&self.scheduler)?).to_string())
+	}
+
+	fn save(&self, path: &str) -> Result<String, String> {
+		let options = WriteOptions { units: self.units, ..WriteOptions::default() };
+		fs::write(path, write_scheduler(&self.scheduler, &options))
+			.map_err(|e| format!("Could not write file: {}\n\r{}", path, e))?;
+		Ok(format!("Saved to {}\n", path))
+	}
+}
+
+/// How to revert a change of the REPL, with the edited tasks only.
+enum Undo<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	/// Previous versions of changed tasks.
+	Restore(Vec<CustomTask<T>>),
+	/// ID of an added task.
+	Remove(String),
+	/// A removed task with it's insertion position, and the previous
+	/// versions of the tasks that depended on it.
+	Insert(usize, CustomTask<T>, Vec<CustomTask<T>>),
+}
+
+impl <T> Undo<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	/// Reverts the change, the scheduler has to be recalculated after.
+	fn apply(self, scheduler: &mut Scheduler<T>) -> Result<(), String> {
+		let previous = match self {
+			Undo::Restore(previous) => previous,
+			Undo::Remove(id) => {
+				scheduler.remove_task(&id)?;
+				vec!{}
+			},
+			Undo::Insert(index, removed, previous) => {
+				scheduler.insert_task(index, removed)?;
+				previous
+			},
+		};
+		for task in previous {
+			match scheduler.get_mut_task_by_name(&task.get_id()) {
+				Some(current) => *current = task,
+				None => { return Err(format!("Unknown task: {}", task.get_id())); },
+			}
+		}
+		Ok(())
+	}
+}
+
+fn schedule<T>(tasks: Vec<CustomTask<T>>) -> Result<Scheduler<T>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let mut scheduler = Scheduler::<T>::new();
+	scheduler.fill_tasklist(tasks)?;
+	scheduler.schedule()?;
+	Ok(scheduler)
+}
+
+fn parse_duration<T>(text: &str, units: &UnitConfig) -> Result<T, String>
+where T: From<i8>
+	+ std::cmp::PartialOrd
+	+ FromStr
+{
+	match units.parse::<T>(text) {
+		Ok(duration) if duration >= 0.into() => Ok(duration),
+		_ => Err(format!("Invalid duration: {}", text)),
+	}
+}
+
+/// Source text of a duration for the writer, only kept with a unit like the
+/// parser does.
+fn duration_source(text: &str) -> Option<String> {
+	text.parse::<UnitDuration>().ok().map(|_| text.to_string())
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::cmp::Ordering::Equal;
 
 use crate::customtask::CustomTask;
@@ -6,6 +6,8 @@ use crate::path::Path;
 
 /// Different state indicators of Scheduler.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 enum SchedulerState {
 	/// Uninitialized.
 	Unknown,
@@ -19,6 +21,11 @@ enum SchedulerState {
 /// calculate critical paths plus the number of
 /// maximum parallel jobs at a time.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(
+	serialize = "T: serde::Serialize",
+	deserialize = "T: serde::Deserialize<'de>"
+)))]
 pub struct Scheduler<T>
 where T: From<i8>
 	+ std::clone::Clone
@@ -30,7 +37,12 @@ where T: From<i8>
 	+ std::cmp::PartialOrd
 	+ std::ops::AddAssign
 {
+	/// Serialized as a list of tasks ordered by ID.
+	#[cfg_attr(feature = "serde", serde(with = "serde_tasks"))]
 	tasks: HashMap<String, CustomTask<T>>,
+	/// Task IDs in the order they were added.
+	#[cfg_attr(feature = "serde", serde(skip))]
+	insertion_order: Vec<String>,
 	state: SchedulerState,
 }
 
@@ -48,6 +60,7 @@ where T: From<i8>
 	pub fn new() -> Self {
 		Scheduler {
 			tasks: HashMap::new(),
+			insertion_order: vec!{},
 			state: SchedulerState::Unknown,
 		}
 	}
@@ -55,12 +68,19 @@ where T: From<i8>
 	/// Ignites all the calculations.
 	pub fn schedule(&mut self) -> Result<(), String>{
 		self.calculate()?;
-		// self.print_output();
 		Ok(())
 	}
 
 	/// Recalculate all parameters without providing new tasks.
+	/// Fails if the dependencies contain a cycle.
 	pub fn calculate(&mut self) -> Result<(), String> {
+		if let Some(cycle) = self.find_cycle() {
+			return Err(format!("Dependency cycle found: {}", cycle.join(" -> ")));
+		}
+		// Stale values of a previous calculation would be taken as final.
+		for task in self.tasks.values_mut() {
+			task.reset_calculations();
+		}
 		self.calculate_es_ef()?;
 		self.calculate_ls_lf()?;
 		self.state = SchedulerState::Ready;
@@ -69,11 +89,38 @@ where T: From<i8>
 
 	pub fn add_task(&mut self, task: CustomTask<T>) -> Result<(), String> {
 		match self.check_task_duplication(&task) {
-			Ok(_) => { self.tasks.insert(task.get_id(), task); return Ok(()); },
+			Ok(_) => {
+				self.insertion_order.push(task.get_id());
+				self.tasks.insert(task.get_id(), task);
+				return Ok(());
+			},
 			Err(e) => { return Err(format!("Failed to add task: {}", e)); }
 		}
 	}
 
+	/// Adds a task at a position of the insertion order, like to put back
+	/// a removed task where it was.
+	pub fn insert_task(&mut self, index: usize, task: CustomTask<T>) -> Result<(), String> {
+		self.check_task_duplication(&task).map_err(|e| format!("Failed to add task: {}", e))?;
+		self.insertion_order.insert(index.min(self.insertion_order.len()), task.get_id());
+		self.tasks.insert(task.get_id(), task);
+		self.state = SchedulerState::Edited;
+		Ok(())
+	}
+
+	/// Removes a task and returns it. Dependencies on it are kept,
+	/// they become dangling.
+	pub fn remove_task(&mut self, task_name: &String) -> Result<CustomTask<T>, String> {
+		match self.tasks.remove(task_name) {
+			Some(task) => {
+				self.insertion_order.retain(|id| id != task_name);
+				self.state = SchedulerState::Edited;
+				Ok(task)
+			},
+			None => Err(format!("Failed to remove task: unknown task ID: {}", task_name)),
+		}
+	}
+
 	/// Sets up a list of tasks, overwriting the already listed ones.
 	pub fn fill_tasklist(&mut self, task_list: Vec<CustomTask<T>>) -> Result<(), String> {
 		self.state = SchedulerState::Edited;
@@ -86,6 +133,7 @@ where T: From<i8>
 			}
 		}
 		self.tasks = new_tasks;
+		self.insertion_order = task_list.iter().map(|task| task.get_id()).collect();
 		Ok(())
 	}
 
@@ -97,6 +145,25 @@ where T: From<i8>
 		}
 	}
 
+	/// Gets all the tasks ordered by ID.
+	pub fn get_tasks(&self) -> Vec<&CustomTask<T>> {
+		let mut tasks: Vec<&CustomTask<T>> = self.tasks.values().collect();
+		tasks.sort_by_key(|task| task.get_id());
+		tasks
+	}
+
+	/// Gets all the tasks in the order they were added.
+	/// Tasks of a deserialized scheduler have no such order, they are ordered by ID.
+	pub fn get_tasks_in_insertion_order(&self) -> Vec<&CustomTask<T>> {
+		let mut tasks: Vec<&CustomTask<T>> = self.insertion_order.iter()
+			.filter_map(|id| self.tasks.get(id))
+			.collect();
+		if tasks.len() < self.tasks.len() {
+			tasks.extend(self.get_tasks().into_iter().filter(|task| !self.insertion_order.contains(&task.get_id())));
+		}
+		tasks
+	}
+
 	/// Gets a task by it's name.
 	pub fn get_task_by_name(&self, task_name: &String) -> Option<&CustomTask<T>> {
 		self.tasks.get(task_name)
@@ -131,6 +198,60 @@ where T: From<i8>
 		successors
 	}
 
+	/// Gets the dependencies that do not refer to any task,
+	/// as `(task ID, dependency ID)` pairs ordered by task ID.
+	pub fn get_dangling_dependencies(&self) -> Vec<(String, String)> {
+		let mut dangling: Vec<(String, String)> = vec!{};
+		for task in self.get_tasks() {
+			for dep_name in task.get_dependencies() {
+				if !self.tasks.contains_key(&dep_name) {
+					dangling.push((task.get_id(), dep_name));
+				}
+			}
+		}
+		dangling
+	}
+
+	/// Looks for a cycle in the dependencies.
+	/// Returns the task IDs along the cycle, the first one repeated at the end.
+	pub fn find_cycle(&self) -> Option<Vec<String>> {
+		let mut finished: HashSet<String> = HashSet::new();
+		for task in self.get_tasks() {
+			let mut trail: Vec<String> = vec!{};
+			if let Some(cycle) = self.find_cycle_from(task, &mut trail, &mut finished) {
+				return Some(cycle);
+			}
+		}
+		None
+	}
+
+	/// Depth first search along the dependencies, `trail` holds the current route.
+	fn find_cycle_from(
+		&self
+		, task: &CustomTask<T>
+		, trail: &mut Vec<String>
+		, finished: &mut HashSet<String>
+	) -> Option<Vec<String>> {
+		if finished.contains(&task.get_id()) {
+			return None;
+		}
+		if let Some(pos) = trail.iter().position(|id| *id == task.get_id()) {
+			let mut cycle = trail[pos..].to_vec();
+			cycle.reverse();
+			cycle.push(cycle[0].clone());
+			return Some(cycle);
+		}
+		trail.push(task.get_id());
+		for dep in self.get_task_dependencies(task) {
+			if let Some(cycle) = self.find_cycle_from(dep, trail, finished) {
+				return Some(cycle);
+			}
+		}
+		trail.pop();
+		finished.insert(task.get_id());
+		None
+	}
+
 	// TODO: optimize
 	fn calculate_es_ef(&mut self) -> Result<(), String> {
 		debug!("Calculating ES-EF");
@@ -300,6 +421,62 @@ where T: From<i8>
 		endpoints
 	}
 
+	/// Gets the weakly connected components of the graph.
+	/// Every component is a sorted list of task IDs, the components are
+	/// ordered by their first ID.
+	pub fn get_component_ids(&self) -> Vec<Vec<String>> {
+		let mut components: Vec<Vec<String>> = vec!{};
+		let mut visited: HashSet<String> = HashSet::new();
+		let mut ids: Vec<&String> = self.tasks.keys().collect();
+		ids.sort();
+		for id in ids {
+			if visited.contains(id) {
+				continue;
+			}
+			visited.insert(id.clone());
+			let mut component: Vec<String> = vec!{};
+			let mut queue: Vec<String> = vec!{id.clone()};
+			while let Some(current) = queue.pop() {
+				let task = &self.tasks[&current];
+				let neighbours = self.get_task_dependencies(task).into_iter()
+					.chain(self.get_task_successors(task));
+				for neighbour in neighbours {
+					if visited.insert(neighbour.get_id()) {
+						queue.push(neighbour.get_id());
+					}
+				}
+				component.push(current);
+			}
+			component.sort();
+			components.push(component);
+		}
+		components
+	}
+
+	/// Splits the graph into independent schedulers, one for each
+	/// weakly connected component.
+	/// Calculated values and the insertion order are kept, so if this
+	/// scheduler is ready, the components are ready as well.
+	pub fn get_components(&self) -> Vec<Scheduler<T>> {
+		let mut components: Vec<Scheduler<T>> = vec!{};
+		for component_ids in self.get_component_ids() {
+			let mut component = Scheduler::new();
+			for id in &component_ids {
+				component.tasks.insert(id.clone(), self.tasks[id].clone());
+			}
+			component.insertion_order = self.insertion_order.iter()
+				.filter(|id| component.tasks.contains_key(*id))
+				.cloned()
+				.collect();
+			component.state = match self.state {
+				SchedulerState::Ready => SchedulerState::Ready,
+				_ => SchedulerState::Edited,
+			};
+			components.push(component);
+		}
+		components
+	}
+
 	/// Returns all paths that are able to trace from the given task.
 	pub fn get_paths_from_task(&self, start_point: &CustomTask<T>, level: u32)
 	-> Vec<Path<T>> {
@@ -368,61 +545,186 @@ where T: From<i8>
 		critical_paths
 	}
 
+	/// Gets the duration of the whole graph, the latest early finish.
+	/// Scheduler has to be in ready state.
+	pub fn get_duration(&self) -> Result<T, String> {
+		if self.state != SchedulerState::Ready {
+			return Err(
+				format!("Scheduler is in state {:?} instead of being ready.", self.state)
+			);
+		}
+		let mut duration: T = 0.into();
+		for task in self.tasks.values() {
+			match task.get_early_finish() {
+				Some(ef) => {
+					if ef > duration {
+						duration = ef;
+					}
+				},
+				None => {
+					return Err(
+						format!("Early finish has not been calculated in task: {}", task.get_id())
+					);
+				},
+			}
+		}
+		Ok(duration)
+	}
+
+	/// Gets the free float of a task: the time it can be delayed without
+	/// delaying any of it's successors.
+	/// Equals the total float if the task has no successors.
+	pub fn get_free_float(&self, task_ref: &CustomTask<T>) -> Result<T, String> {
+		let ef = match task_ref.get_early_finish() {
+			Some(ef) => ef,
+			None => {
+				return Err(
+					format!("Early finish has not been calculated in task: {}", task_ref.get_id())
+				);
+			},
+		};
+		let mut min_successor_es: Option<T> = None;
+		for successor in self.get_task_successors(task_ref) {
+			match successor.get_early_start() {
+				Some(es) => {
+					if min_successor_es.is_none_or(|min| es < min) {
+						min_successor_es = Some(es);
+					}
+				},
+				None => {
+					return Err(
+						format!("Early start has not been calculated in task: {}", successor.get_id())
+					);
+				},
+			}
+		}
+		match min_successor_es {
+			Some(es) => Ok(es - ef),
+			None => task_ref.get_total_float(),
+		}
+	}
+
+	/// Gets the IDs of the tasks that are part of any critical path.
+	/// Scheduler has to be in ready state.
+	pub fn get_critical_task_ids(&self) -> Result<HashSet<String>, String> {
+		self.get_duration()?;
+		let mut critical_ids: HashSet<String> = HashSet::new();
+		for path in self.get_critical_paths() {
+			critical_ids.extend(path.get_task_ids());
+		}
+		Ok(critical_ids)
+	}
+
 	/// Calculates the maximum number of parallel jobs at a time.
 	/// Scheduler has to be in ready state.
 	pub fn get_parallelism(&self) -> Result<u32, String> {
 		debug!("Getting parallel task count:");
-		if self.state == SchedulerState::Ready {
-			let mut ef_list: Vec<Option<T>> = vec!{Some(0.into())};
-			let mut max_parallel = 0;
-			for (_, task) in &self.tasks {
-				match task.get_early_finish() {
-					Some(_) => {
-						ef_list.push(task.get_early_finish());
-					},
-					None => {
-						return Err(
-							format!(
-								"Some of the early finish values have not been calculated! Task: {}"
-								, task.get_id()
-							)
-						);
-					},
-				}
-			}
-			ef_list.dedup();
-			ef_list.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Equal));
-			for ef_idx in 0..ef_list.len() - 1 {
-				let section_start = ef_list[ef_idx].unwrap();
-				let section_end = ef_list[ef_idx + 1].unwrap();
-				let section_parallel = self.tasks.iter().filter(
-					| (_, task) |
-					task.get_early_start().unwrap() <= section_start
-					&& section_end <= task.get_early_finish().unwrap()
-					).collect::<Vec<(&String, &CustomTask<T>)>>();
-				if section_parallel.len() > max_parallel {
-					max_parallel = section_parallel.len();
-					debug!("Section: {} .. {}", section_start, section_end);
-					debug!("section tasks: {:?}", section_parallel);
-				}
+		let mut max_parallel = 0;
+		for (section_start, section_end, section_parallel) in self.get_concurrency_profile()? {
+			if section_parallel > max_parallel {
+				max_parallel = section_parallel;
+				debug!("Section: {} .. {}", section_start, section_end);
 			}
-			return Ok(max_parallel.try_into().unwrap());
-		} else {
+		}
+		Ok(max_parallel)
+	}
+
+	/// Splits the time line into sections at the early finishes and
+	/// counts the tasks running in each section.
+	/// Returns `(section start, section end, task count)` items in time order.
+	/// Scheduler has to be in ready state.
+	pub fn get_concurrency_profile(&self) -> Result<Vec<(T, T, u32)>, String> {
+		if self.state != SchedulerState::Ready {
 			return Err(
 				format!("Scheduler is in state {:?} instead of being ready.", self.state)
 			);
 		}
-	}
-
-	fn print_output(&self) {
-		let critical_paths = self.get_critical_paths();
-		println!("Critical paths: {}", critical_paths.len());
-		for path in &critical_paths {
-			println!("\tCritical path: {}", path.get_path_string());
-			println!("\tPath duration: {}", path.get_dur());
+		// Every early start is either 0 or the early finish of a dependency.
+		let mut ef_list: Vec<T> = vec!{0.into()};
+		for task in self.tasks.values() {
+			match task.get_early_finish() {
+				Some(ef) => {
+					ef_list.push(ef);
+				},
+				None => {
+					return Err(
+						format!(
+							"Some of the early finish values have not been calculated! Task: {}"
+							, task.get_id()
+						)
+					);
+				},
+			}
+		}
+		ef_list.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Equal));
+		ef_list.dedup();
+		let mut profile: Vec<(T, T, u32)> = vec!{};
+		for section in ef_list.windows(2) {
+			let (section_start, section_end) = (section[0], section[1]);
+			let section_parallel = self.tasks.values().filter(
+				| task |
+				task.get_early_start().is_some_and(|es| es <= section_start)
+				&& task.get_early_finish().is_some_and(|ef| section_end <= ef)
+				).count();
+			profile.push((section_start, section_end, section_parallel as u32));
 		}
-		println!("Number of maximum parallel jobs: {}", self.get_parallelism().unwrap());
+		Ok(profile)
 	}
 
 }
 
+/// (De)serializes the task map as a list ordered by task ID,
+/// so the output does not depend on the hash order.
+#[cfg(feature = "serde")]
+mod serde_tasks {
+	use std::collections::HashMap;
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	use crate::customtask::CustomTask;
+
+	pub fn serialize<S, T>(tasks: &HashMap<String, CustomTask<T>>, serializer: S)
+	-> Result<S::Ok, S::Error>
+	where S: Serializer,
+		T: From<i8>
+		+ std::clone::Clone
+		+ std::marker::Copy
+		+ std::ops::Sub::<Output = T>
+		+ std::ops::Add<Output = T>
+		+ std::fmt::Display
+		+ std::fmt::Debug
+		+ std::cmp::PartialOrd
+		+ std::ops::AddAssign
+		+ Serialize
+	{
+		let mut task_list: Vec<&CustomTask<T>> = tasks.values().collect();
+		task_list.sort_by_key(|task| task.get_id());
+		task_list.serialize(serializer)
+	}
+
+	pub fn deserialize<'de, D, T>(deserializer: D)
+	-> Result<HashMap<String, CustomTask<T>>, D::Error>
+	where D: Deserializer<'de>,
+		T: From<i8>
+		+ std::clone::Clone
+		+ std::marker::Copy
+		+ std::ops::Sub::<Output = T>
+		+ std::ops::Add<Output = T>
+		+ std::fmt::Display
+		+ std::fmt::Debug
+		+ std::cmp::PartialOrd
+		+ std::ops::AddAssign
+		+ Deserialize<'de>
+	{
+		let task_list = Vec::<CustomTask<T>>::deserialize(deserializer)?;
+		let mut tasks: HashMap<String, CustomTask<T>> = HashMap::new();
+		for task in task_list {
+			if tasks.contains_key(&task.get_id()) {
+				return Err(serde::de::Error::custom(
+					format!("task ID duplication: {}", task.get_id())
+				));
+			}
+			tasks.insert(task.get_id(), task);
+		}
+		Ok(tasks)
+	}
+}
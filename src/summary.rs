@@ -0,0 +1,219 @@
+use std::fmt;
+
+use crate::numeric::{format_number, to_f64};
+use crate::path::Path;
+use crate::scheduler::Scheduler;
+
+/// A critical path of the summary and of the JSON report.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CriticalPath<T> {
+	pub duration: T,
+	/// Task IDs from the start point to the end point.
+	pub tasks: Vec<String>,
+}
+
+impl <T> CriticalPath<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	pub fn from_path(path: &Path<T>) -> Self {
+		CriticalPath {
+			duration: path.get_dur(),
+			tasks: path.get_task_ids(),
+		}
+	}
+}
+
+/// Statistics of the total floats of the tasks.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FloatStatistics<T> {
+	pub min: T,
+	pub max: T,
+	pub mean: f64,
+	/// Number of tasks without any float.
+	pub zero_float_count: usize,
+}
+
+/// The key numbers of a calculated schedule.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduleSummary<T> {
+	/// Duration of the project, the latest early finish.
+	pub duration: T,
+	/// IDs of the tasks without dependencies, ordered.
+	pub startpoints: Vec<String>,
+	/// IDs of the tasks without successors, ordered.
+	pub endpoints: Vec<String>,
+	pub critical_paths: Vec<CriticalPath<T>>,
+	pub task_count: usize,
+	/// Number of tasks on any critical path.
+	pub critical_task_count: usize,
+	/// Maximum number of parallel tasks at a time.
+	pub parallelism: u32,
+	/// Float statistics, `None` if there are no tasks.
+	pub float: Option<FloatStatistics<T>>,
+}
+
+impl <T> ScheduleSummary<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	/// Collects the summary of a scheduler.
+	/// Scheduler has to be in ready state.
+	pub fn from_scheduler(scheduler: &Scheduler<T>) -> Result<Self, String> {
+		let duration = scheduler.get_duration()?;
+		let mut startpoints: Vec<String> = scheduler.get_startpoints().iter()
+			.map(|task| task.get_id())
+			.collect();
+		startpoints.sort();
+		let mut endpoints: Vec<String> = scheduler.get_endpoints().iter()
+			.map(|task| task.get_id())
+			.collect();
+		endpoints.sort();
+		let mut critical_paths: Vec<CriticalPath<T>> = scheduler.get_critical_paths().iter()
+			.map(CriticalPath::from_path)
+			.collect();
+		critical_paths.sort_by(|a, b| a.tasks.cmp(&b.tasks));
+
+		let mut float: Option<FloatStatistics<T>> = None;
+		let mut float_sum = 0.0;
+		for task in scheduler.get_tasks() {
+			let tf = task.get_total_float()?;
+			float_sum += to_f64(tf);
+			let statistics = float.get_or_insert(FloatStatistics {
+				min: tf,
+				max: tf,
+				mean: 0.0,
+				zero_float_count: 0,
+			});
+			if tf < statistics.min {
+				statistics.min = tf;
+			}
+			if tf > statistics.max {
+				statistics.max = tf;
+			}
+			if tf == 0.into() {
+				statistics.zero_float_count += 1;
+			}
+		}
+		let task_count = scheduler.get_tasks().len();
+		if let Some(statistics) = float.as_mut() {
+			statistics.mean = float_sum / task_count as f64;
+		}
+
+		Ok(ScheduleSummary {
+			duration,
+			startpoints,
+			endpoints,
+			critical_paths,
+			task_count,
+			critical_task_count: scheduler.get_critical_task_ids()?.len(),
+			parallelism: scheduler.get_parallelism()?,
+			float,
+		})
+	}
+}
+
+/// Renders a summary, so every output format shows the same numbers.
+pub trait SummaryFormatter<T> {
+	fn format(&self, summary: &ScheduleSummary<T>) -> Result<String, String>;
+}
+
+/// Plain text output, one value per line.
+pub struct PlainTextFormatter;
+
+/// Markdown table of the values and a list of the critical paths.
+pub struct MarkdownFormatter;
+
+/// Pretty printed JSON of the summary.
+#[cfg(feature = "json")]
+pub struct JsonFormatter;
+
+impl <T> SummaryFormatter<T> for PlainTextFormatter
+where T: std::fmt::Display
+{
+	fn format(&self, summary: &ScheduleSummary<T>) -> Result<String, String> {
+		let mut output = String::new();
+		for (name, value) in summary_rows(summary) {
+			output.push_str(&format!("{}: {}\n", name, value));
+		}
+		output.push_str(&format!("Critical paths: {}\n", summary.critical_paths.len()));
+		for path in &summary.critical_paths {
+			output.push_str(&format!("\t{} ({})\n", path.tasks.join(" -> "), path.duration));
+		}
+		Ok(output)
+	}
+}
+
+impl <T> SummaryFormatter<T> for MarkdownFormatter
+where T: std::fmt::Display
+{
+	fn format(&self, summary: &ScheduleSummary<T>) -> Result<String, String> {
+		let mut output = String::from("| Metric | Value |\n| --- | --- |\n");
+		for (name, value) in summary_rows(summary) {
+			output.push_str(&format!("| {} | {} |\n", name, value.replace('|', "\\|")));
+		}
+		output.push_str("\n**Critical paths**\n\n");
+		for path in &summary.critical_paths {
+			output.push_str(&format!("1. {} ({})\n", path.tasks.join(" → "), path.duration));
+		}
+		Ok(output)
+	}
+}
+
+#[cfg(feature = "json")]
+impl <T> SummaryFormatter<T> for JsonFormatter
+where T: serde::Serialize
+{
+	fn format(&self, summary: &ScheduleSummary<T>) -> Result<String, String> {
+		serde_json::to_string_pretty(summary)
+			.map_err(|e| format!("Could not write JSON summary: {}", e))
+	}
+}
+
+/// Plain text rendering.
+impl <T> fmt::Display for ScheduleSummary<T>
+where T: std::fmt::Display
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let output = PlainTextFormatter.format(self).map_err(|_| fmt::Error)?;
+		write!(f, "{}", output)
+	}
+}
+
+/// Names and values of the scalar fields, shared by the text formatters.
+fn summary_rows<T>(summary: &ScheduleSummary<T>) -> Vec<(&'static str, String)>
+where T: std::fmt::Display
+{
+	let mut rows = vec!{
+		("Project duration", summary.duration.to_string()),
+		("Tasks", summary.task_count.to_string()),
+		("Critical tasks", summary.critical_task_count.to_string()),
+		("Maximum parallel tasks", summary.parallelism.to_string()),
+		("Start points", summary.startpoints.join(", ")),
+		("End points", summary.endpoints.join(", ")),
+	};
+	if let Some(float) = &summary.float {
+		rows.push(("Minimum float", float.min.to_string()));
+		rows.push(("Maximum float", float.max.to_string()));
+		rows.push(("Mean float", format_number(float.mean)));
+		rows.push(("Tasks without float", float.zero_float_count.to_string()));
+	}
+	rows
+}
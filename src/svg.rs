@@ -0,0 +1,244 @@
+use crate::customtask::CustomTask;
+use crate::numeric::{format_number, tick_step, to_f64};
+use crate::scheduler::Scheduler;
+
+/// Settings of the SVG Gantt chart.
+#[derive(Clone, Debug)]
+pub struct SvgOptions {
+	/// Width of the time area in pixels, labels are drawn on the left of it.
+	pub chart_width: f64,
+	/// Width of the task label column in pixels.
+	pub label_width: f64,
+	/// Height of one task row in pixels.
+	pub row_height: f64,
+	/// Draws arrows from the predecessors to the successors.
+	pub show_dependencies: bool,
+	/// Title drawn above the chart.
+	pub title: Option<String>,
+}
+
+impl Default for SvgOptions {
+	fn default() -> Self {
+		SvgOptions {
+			chart_width: 800.0,
+			label_width: 140.0,
+			row_height: 24.0,
+			show_dependencies: true,
+			title: None,
+		}
+	}
+}
+
+static CRITICAL_COLOR: &str = "#d9534f";
+static TASK_COLOR: &str = "#4a7fd4";
+static FLOAT_COLOR: &str = "#9a9a9a";
+
+/// Renders the scheduled tasks as a self-contained SVG Gantt chart.
+///
+/// Every task has a bar from it's early start to it's early finish and a
+/// whisker to it's late finish showing the float. Zero duration tasks and
+/// tasks with `milestone` metadata are drawn as diamonds.
+/// Scheduler has to be in ready state.
+pub fn render_gantt<T>(scheduler: &Scheduler<T>, options: &SvgOptions) -> Result<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let duration = to_f64(scheduler.get_duration()?);
+	let critical_ids = scheduler.get_critical_task_ids()?;
+	let mut tasks = scheduler.get_tasks();
+	tasks.sort_by(|a, b| a.get_early_start().partial_cmp(&b.get_early_start())
+		.unwrap_or(std::cmp::Ordering::Equal));
+
+	let scale = if duration > 0.0 { options.chart_width / duration } else { options.chart_width };
+	let title_height = if options.title.is_some() { options.row_height * 1.5 } else { 0.0 };
+	let axis_height = options.row_height;
+	let top = title_height + axis_height;
+	let left = options.label_width;
+	let width = left + options.chart_width + 20.0;
+	let height = top + options.row_height * tasks.len() as f64 + 10.0;
+	let x_of = |value: f64| left + value * scale;
+	let row_of = |id: &String| tasks.iter().position(|task| task.get_id() == *id);
+	let mid_y = |row: usize| top + options.row_height * (row as f64 + 0.5);
+
+	let mut svg = format!(
+		"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n"
+		, w = format_number(width)
+		, h = format_number(height)
+	);
+	svg.push_str(&format!(
+		"\t<defs><marker id=\"arrow\" markerWidth=\"8\" markerHeight=\"8\" refX=\"8\" refY=\"4\" orient=\"auto\"><path d=\"M0,0 L8,4 L0,8 z\" fill=\"{}\"/></marker></defs>\n"
+		, FLOAT_COLOR
+	));
+	svg.push_str(&format!("\t<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n", format_number(width), format_number(height)));
+	if let Some(title) = &options.title {
+		svg.push_str(&format!(
+			"\t<text x=\"{}\" y=\"{}\" font-size=\"16\" font-weight=\"bold\">{}</text>\n"
+			, format_number(left)
+			, format_number(title_height * 0.7)
+			, escape_xml(title)
+		));
+	}
+
+	// Time axis with grid lines.
+	let step = tick_step(duration, 10);
+	let mut tick = 0.0;
+	while tick <= duration + step * 1e-9 {
+		svg.push_str(&format!(
+			"\t<line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\" stroke=\"#e0e0e0\"/>\n"
+			, format_number(top - 4.0)
+			, format_number(height - 10.0)
+			, x = format_number(x_of(tick))
+		));
+		svg.push_str(&format!(
+			"\t<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"#555\">{}</text>\n"
+			, format_number(x_of(tick))
+			, format_number(top - 8.0)
+			, format_number(tick)
+		));
+		tick += step;
+	}
+
+	for (row, task) in tasks.iter().enumerate() {
+		let es = to_f64(task.get_early_start().unwrap_or(0.into()));
+		let ef = to_f64(task.get_early_finish().unwrap_or(0.into()));
+		let lf = to_f64(task.get_late_finish().unwrap_or(0.into()));
+		let y = mid_y(row);
+		let color = if critical_ids.contains(&task.get_id()) { CRITICAL_COLOR } else { TASK_COLOR };
+		svg.push_str(&format!(
+			"\t<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n"
+			, format_number(left - 8.0)
+			, format_number(y + 4.0)
+			, escape_xml(&task_label(task))
+		));
+		if lf > ef {
+			svg.push_str(&format!(
+				"\t<line x1=\"{}\" y1=\"{y}\" x2=\"{x2}\" y2=\"{y}\" stroke=\"{c}\" stroke-dasharray=\"3,2\"/>\n\t<line x1=\"{x2}\" y1=\"{}\" x2=\"{x2}\" y2=\"{}\" stroke=\"{c}\"/>\n"
+				, format_number(x_of(ef))
+				, format_number(y - 4.0)
+				, format_number(y + 4.0)
+				, y = format_number(y)
+				, x2 = format_number(x_of(lf))
+				, c = FLOAT_COLOR
+			));
+		}
+		if is_milestone(task) {
+			let size = options.row_height * 0.3;
+			let x = x_of(es);
+			svg.push_str(&format!(
+				"\t<polygon points=\"{},{} {},{} {},{} {},{}\" fill=\"{}\"><title>{}</title></polygon>\n"
+				, format_number(x), format_number(y - size)
+				, format_number(x + size), format_number(y)
+				, format_number(x), format_number(y + size)
+				, format_number(x - size), format_number(y)
+				, color
+				, escape_xml(&task_title(task))
+			));
+		} else {
+			let bar_height = options.row_height * 0.6;
+			svg.push_str(&format!(
+				"\t<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\" fill=\"{}\"><title>{}</title></rect>\n"
+				, format_number(x_of(es))
+				, format_number(y - bar_height / 2.0)
+				, format_number((ef - es) * scale)
+				, format_number(bar_height)
+				, color
+				, escape_xml(&task_title(task))
+			));
+		}
+	}
+
+	if options.show_dependencies {
+		for (row, task) in tasks.iter().enumerate() {
+			let es = to_f64(task.get_early_start().unwrap_or(0.into()));
+			for dep in scheduler.get_task_dependencies(task) {
+				let dep_row = match row_of(&dep.get_id()) {
+					Some(dep_row) => dep_row,
+					None => { continue; },
+				};
+				let dep_ef = to_f64(dep.get_early_finish().unwrap_or(0.into()));
+				let elbow = x_of(dep_ef) + 6.0;
+				svg.push_str(&format!(
+					"\t<path d=\"M{},{} H{} V{} H{}\" fill=\"none\" stroke=\"{}\" marker-end=\"url(#arrow)\"/>\n"
+					, format_number(x_of(dep_ef))
+					, format_number(mid_y(dep_row))
+					, format_number(elbow)
+					, format_number(mid_y(row))
+					, format_number(x_of(es).max(elbow))
+					, FLOAT_COLOR
+				));
+			}
+		}
+	}
+	svg.push_str("</svg>\n");
+	Ok(svg)
+}
+
+/// A task is a milestone if it has no duration or it is marked as one.
+pub(crate) fn is_milestone<T>(task: &CustomTask<T>) -> bool
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	task.get_duration() == 0.into()
+		|| task.get_metadata("milestone").as_deref() == Some("true")
+}
+
+/// Shown label of a task: it's name if there is one, otherwise it's ID.
+pub(crate) fn task_label<T>(task: &CustomTask<T>) -> String
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	task.get_metadata("name").unwrap_or(task.get_id())
+}
+
+/// Tooltip of a task with the calculated values.
+fn task_title<T>(task: &CustomTask<T>) -> String
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let value = |value: Option<T>| value.map_or("-".to_string(), |v| v.to_string());
+	format!(
+		"{} ({}) ES {} EF {} LS {} LF {} TF {}"
+		, task.get_id()
+		, task.get_duration()
+		, value(task.get_early_start())
+		, value(task.get_early_finish())
+		, value(task.get_late_start())
+		, value(task.get_late_finish())
+		, value(task.get_total_float().ok())
+	)
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
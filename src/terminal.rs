@@ -0,0 +1,211 @@
+use crate::numeric::{format_number, tick_step, to_f64};
+use crate::scheduler::Scheduler;
+use crate::svg::is_milestone;
+
+/// Row order of the terminal Gantt chart.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GanttSort {
+	/// By early start, then by ID.
+	EarlyStart,
+	/// By ID.
+	Id,
+	/// By total float, then by early start.
+	Float,
+}
+
+/// Settings of the terminal Gantt chart.
+#[derive(Clone, Debug)]
+pub struct TerminalOptions {
+	/// Width of a whole line in characters, including the labels.
+	pub width: usize,
+	/// Row order.
+	pub sort: GanttSort,
+	/// Time units per character. If it is not set, the chart fits the width,
+	/// a scale making the chart wider than `width` is an error.
+	pub scale: Option<f64>,
+	/// Colors critical tasks red and float dim with ANSI escape codes.
+	pub color: bool,
+	/// Draws with Unicode block characters instead of ASCII.
+	pub unicode: bool,
+}
+
+impl Default for TerminalOptions {
+	fn default() -> Self {
+		TerminalOptions {
+			width: 80,
+			sort: GanttSort::EarlyStart,
+			scale: None,
+			color: true,
+			unicode: true,
+		}
+	}
+}
+
+static ANSI_RED: &str = "\x1b[31m";
+static ANSI_DIM: &str = "\x1b[2m";
+static ANSI_RESET: &str = "\x1b[0m";
+
+/// Maximum width of the label column.
+static MAX_LABEL_WIDTH: usize = 24;
+
+/// Renders the scheduled tasks as a text Gantt chart, one row per task.
+/// Bars span from the early start to the early finish, the float is drawn
+/// with a lighter segment up to the late finish.
+/// Scheduler has to be in ready state.
+pub fn render_gantt<T>(scheduler: &Scheduler<T>, options: &TerminalOptions) -> Result<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	let duration = to_f64(scheduler.get_duration()?);
+	let critical_ids = scheduler.get_critical_task_ids()?;
+	let mut tasks = scheduler.get_tasks();
+	let compare = |a: Option<T>, b: Option<T>| a.partial_cmp(&b)
+		.unwrap_or(std::cmp::Ordering::Equal);
+	match options.sort {
+		GanttSort::EarlyStart => tasks.sort_by(|a, b| compare(a.get_early_start(), b.get_early_start())),
+		GanttSort::Id => {},
+		GanttSort::Float => tasks.sort_by(|a, b|
+			compare(a.get_total_float().ok(), b.get_total_float().ok())
+				.then(compare(a.get_early_start(), b.get_early_start()))
+		),
+	}
+
+	let label_width = tasks.iter()
+		.map(|task| task.get_id().chars().count())
+		.max()
+		.unwrap_or(0)
+		.clamp(2, MAX_LABEL_WIDTH);
+	let chart_width = options.width.saturating_sub(label_width + 3).max(10);
+	let scale = match options.scale {
+		Some(scale) if scale > 0.0 => scale,
+		Some(_) => { return Err("Time scale has to be greater than 0.".to_string()); },
+		None if duration > 0.0 => duration / chart_width as f64,
+		None => 1.0,
+	};
+	let columns = ((duration / scale).ceil() as usize).max(1);
+	if columns > chart_width {
+		return Err(format!(
+			"Time scale {} needs {} columns, only {} fit the width: use a scale of at least {}."
+			, scale
+			, columns
+			, chart_width
+			, format_number(duration / chart_width as f64)
+		));
+	}
+	let (bar, float, milestone, vertical) = if options.unicode {
+		('█', '░', '◆', '│')
+	} else {
+		('#', '-', '*', '|')
+	};
+
+	let mut output = String::new();
+	output.push_str(&format!("{:width$} {}\n", "", axis_line(duration, scale, columns), width = label_width + 1));
+	for task in tasks {
+		let es = to_f64(task.get_early_start().unwrap_or(0.into()));
+		let ef = to_f64(task.get_early_finish().unwrap_or(0.into()));
+		let lf = to_f64(task.get_late_finish().unwrap_or(0.into()));
+		let critical = critical_ids.contains(&task.get_id());
+		let cells = timeline_cells(
+			(es, ef, lf)
+			, is_milestone(task)
+			, 0.0
+			, scale
+			, columns
+			, (bar, float, milestone)
+		);
+
+		let mut label: String = task.get_id().chars().take(label_width).collect();
+		if task.get_id().chars().count() > label_width {
+			label.pop();
+			label.push('~');
+		}
+		output.push_str(&format!("{:width$} {}", label, vertical, width = label_width));
+		let mut current_style = "";
+		for cell in cells {
+			let style = if !options.color || cell == ' ' {
+				""
+			} else if cell == float {
+				ANSI_DIM
+			} else if critical {
+				ANSI_RED
+			} else {
+				""
+			};
+			if style != current_style {
+				if !current_style.is_empty() {
+					output.push_str(ANSI_RESET);
+				}
+				output.push_str(style);
+				current_style = style;
+			}
+			output.push(cell);
+		}
+		if !current_style.is_empty() {
+			output.push_str(ANSI_RESET);
+		}
+		output.push(vertical);
+		output.push('\n');
+	}
+	Ok(output)
+}
+
+/// Characters of one task row from the time `start` on, `scale` time units
+/// per character. `times` are the early start, early finish and late finish,
+/// `symbols` are the bar, float and milestone characters.
+pub(crate) fn timeline_cells(
+	times: (f64, f64, f64)
+	, milestone: bool
+	, start: f64
+	, scale: f64
+	, columns: usize
+	, symbols: (char, char, char)
+) -> Vec<char> {
+	let (es, ef, lf) = times;
+	let (bar, float, milestone_symbol) = symbols;
+	let mut cells: Vec<char> = vec!{' '; columns};
+	for (column, cell) in cells.iter_mut().enumerate() {
+		let middle = start + (column as f64 + 0.5) * scale;
+		if es <= middle && middle < ef {
+			*cell = bar;
+		} else if ef <= middle && middle < lf {
+			*cell = float;
+		}
+	}
+	// Short tasks and milestones get at least one character.
+	let start_column = ((es - start) / scale).floor();
+	if columns > 0 && start_column >= 0.0 && start_column <= columns as f64 {
+		let start_column = (start_column as usize).min(columns - 1);
+		if milestone {
+			cells[start_column] = milestone_symbol;
+		} else if !cells.contains(&bar) {
+			cells[start_column] = bar;
+		}
+	}
+	cells
+}
+
+/// Time axis with round tick labels above the chart columns.
+fn axis_line(duration: f64, scale: f64, columns: usize) -> String {
+	let mut axis: Vec<char> = vec!{' '; columns + 8};
+	// Keep at least 8 characters between labels.
+	let step = tick_step(duration, (columns / 8).max(1) as u32).max(scale);
+	let mut tick = 0.0;
+	let mut free_from = 0;
+	while tick <= duration + step * 1e-9 {
+		let column = (tick / scale).round() as usize;
+		let label: Vec<char> = format_number(tick).chars().collect();
+		if column >= free_from && column + label.len() <= axis.len() {
+			axis[column..column + label.len()].copy_from_slice(&label);
+			free_from = column + label.len() + 1;
+		}
+		tick += step;
+	}
+	axis.into_iter().collect::<String>().trim_end().to_string()
+}
@@ -40,5 +40,1035 @@ mod tests {
 		assert_eq!(endpoints[0].get_total_float(), Ok(0));
 		assert_eq!(scheduler.get_parallelism(), Ok(2));
 	}
-}
 
+	#[test]
+	fn cpm_components() {
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("A".to_string(), 2, vec!{}),
+			CustomTask::new("B".to_string(), 3, vec!{"A".to_string()}),
+			CustomTask::new("C".to_string(), 4, vec!{"A".to_string()}),
+			CustomTask::new("X".to_string(), 5, vec!{}),
+			CustomTask::new("Y".to_string(), 1, vec!{"X".to_string()}),
+		}).unwrap();
+		scheduler.schedule().unwrap();
+		assert_eq!(
+			scheduler.get_component_ids(),
+			vec!{
+				vec!{"A".to_string(), "B".to_string(), "C".to_string()},
+				vec!{"X".to_string(), "Y".to_string()},
+			}
+		);
+		let components = scheduler.get_components();
+		assert_eq!(components.len(), 2);
+		assert_eq!(components[0].get_startpoints().len(), 1);
+		assert_eq!(components[0].get_endpoints().len(), 2);
+		assert_eq!(components[0].get_duration(), Ok(6));
+		assert_eq!(components[0].get_parallelism(), Ok(2));
+		assert_eq!(components[0].get_critical_paths()[0].get_path_string(), "A(2)->C(4)->");
+		assert_eq!(components[1].get_duration(), Ok(6));
+		assert_eq!(components[1].get_parallelism(), Ok(1));
+		assert_eq!(scheduler.get_duration(), Ok(6));
+
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("Y".to_string(), 1, vec!{"X".to_string()}),
+			CustomTask::new("C".to_string(), 4, vec!{"A".to_string()}),
+			CustomTask::new("X".to_string(), 5, vec!{}),
+			CustomTask::new("A".to_string(), 2, vec!{}),
+		}).unwrap();
+		let order: Vec<Vec<String>> = scheduler.get_components().iter()
+			.map(|component| component.get_tasks_in_insertion_order().iter().map(|task| task.get_id()).collect())
+			.collect();
+		assert_eq!(order, vec!{
+			vec!{"C".to_string(), "A".to_string()},
+			vec!{"Y".to_string(), "X".to_string()},
+		});
+	}
+
+	#[test]
+	fn cpm_recalculation() {
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("A".to_string(), 2, vec!{}),
+			CustomTask::new("B".to_string(), 3, vec!{"A".to_string()}),
+			CustomTask::new("C".to_string(), 1, vec!{"A".to_string()}),
+			CustomTask::new("D".to_string(), 1, vec!{"B".to_string(), "C".to_string()}),
+		}).unwrap();
+		scheduler.schedule().unwrap();
+		assert_eq!(scheduler.get_task_by_name(&"C".to_string()).unwrap().get_total_float(), Ok(2));
+		// Shortening a task has to lower the values of the previous calculation too.
+		scheduler.get_mut_task_by_name(&"B".to_string()).unwrap().set_duration(1);
+		scheduler.calculate().unwrap();
+		assert_eq!(scheduler.get_duration(), Ok(4));
+		assert_eq!(scheduler.get_task_by_name(&"C".to_string()).unwrap().get_late_finish(), Some(3));
+		assert_eq!(scheduler.get_task_by_name(&"C".to_string()).unwrap().get_total_float(), Ok(0));
+		scheduler.get_mut_task_by_name(&"A".to_string()).unwrap().set_dependencies(vec!{"B".to_string()});
+		assert_eq!(scheduler.calculate(), Err("Dependency cycle found: B -> A -> B".to_string()));
+	}
+
+	#[test]
+	fn cpm_parallelism() {
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("A1".to_string(), 2, vec!{}),
+			CustomTask::new("B1".to_string(), 2, vec!{"A1".to_string()}),
+			CustomTask::new("A2".to_string(), 2, vec!{}),
+			CustomTask::new("B2".to_string(), 2, vec!{"A2".to_string()}),
+			CustomTask::new("A3".to_string(), 2, vec!{}),
+			CustomTask::new("B3".to_string(), 2, vec!{"A3".to_string()}),
+		}).unwrap();
+		scheduler.schedule().unwrap();
+		// Equal early finishes have to split the time line only once.
+		assert_eq!(scheduler.get_concurrency_profile(), Ok(vec!{(0, 2, 3), (2, 4, 3)}));
+		assert_eq!(scheduler.get_parallelism(), Ok(3));
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn cpm_serde_roundtrip() {
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("B".to_string(), 3, vec!{"A".to_string()}),
+			CustomTask::new("A".to_string(), 2, vec!{}),
+		}).unwrap();
+		scheduler.schedule().unwrap();
+		let json = serde_json::to_string(&scheduler).unwrap();
+		assert_eq!(
+			json,
+			concat!(
+				r#"{"tasks":[{"id":"A","duration":2,"dependencies":[],"early_start":0,"#,
+				r#""early_finish":2,"late_start":0,"late_finish":2,"total_float":0},"#,
+				r#"{"id":"B","duration":3,"dependencies":["A"],"early_start":2,"#,
+				r#""early_finish":5,"late_start":2,"late_finish":5,"total_float":0}],"#,
+				r#""state":"ready"}"#,
+			)
+		);
+		let restored: Scheduler<i32> = serde_json::from_str(&json).unwrap();
+		assert_eq!(restored.get_duration(), Ok(5));
+		let path_json = serde_json::to_string(&restored.get_critical_paths()[0]).unwrap();
+		let path: crate::Path<i32> = serde_json::from_str(&path_json).unwrap();
+		assert_eq!(path.get_path_string(), "A(2)->B(3)->");
+
+		let input: CustomTask<f64> = serde_json::from_str(
+			r#"{"id":"C","duration":1.5}"#
+		).unwrap();
+		assert_eq!(input.get_duration(), 1.5);
+		assert_eq!(input.get_early_start(), None);
+	}
+
+	#[cfg(feature = "json")]
+	#[test]
+	fn cpm_json_project_and_report() {
+		let tasks = crate::json::parse_str::<f64>(r#"{"tasks": [
+			{"id": "A", "duration": 1.5},
+			{"id": "B", "duration": 2, "dependencies": ["A"], "metadata": {"owner": "me"}},
+			{"id": "C", "duration": 1, "dependencies": ["A"]},
+			{"id": "D", "duration": 0.5, "dependencies": ["B", "C"]}
+		]}"#).unwrap();
+		assert_eq!(tasks[1].get_metadata("owner"), Some("me".to_string()));
+		let mut scheduler = Scheduler::<f64>::new();
+		scheduler.fill_tasklist(tasks).unwrap();
+		scheduler.schedule().unwrap();
+		let report = crate::json::create_report(&scheduler).unwrap();
+		assert_eq!(report.duration, 4.0);
+		assert_eq!(report.parallelism, 2);
+		assert_eq!(report.critical_paths[0].tasks, vec!{"A".to_string(), "B".to_string(), "D".to_string()});
+		assert!(!report.tasks[2].critical);
+		assert_eq!((report.tasks[2].total_float, report.tasks[2].free_float), (1.0, 1.0));
+		let project = crate::json::write_project(&scheduler).unwrap();
+		assert_eq!(crate::json::parse_str::<f64>(&project).unwrap().len(), 4);
+		assert!(crate::json::parse_str::<i32>(r#"{"tasks": [{"id": "A", "duration": -1}]}"#).is_err());
+		assert!(crate::json::parse_str::<i32>(r#"{"tasks": [{"id": "A", "duraton": 1}]}"#).is_err());
+	}
+
+	#[test]
+	fn cpm_csv_import_export() {
+		let columns = crate::csv::CsvColumns {
+			name: Some("name".to_string()),
+			..Default::default()
+		};
+		let tasks = crate::csv::parse_str::<i32>(
+			"id,duration,predecessors,name\n\
+			A,2,,\"Design, first\"\n\
+			B,3,A,Build\n\
+			C,1,A,\n\
+			D,1,B; C,Ship\n",
+			&columns
+		).unwrap();
+		assert_eq!(tasks[0].get_metadata("name"), Some("Design, first".to_string()));
+		assert_eq!(tasks[3].get_dependencies(), vec!{"B".to_string(), "C".to_string()});
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(tasks).unwrap();
+		scheduler.schedule().unwrap();
+		assert_eq!(
+			crate::csv::write_schedule(&scheduler, ',').unwrap(),
+			"id,duration,early_start,early_finish,late_start,late_finish,total_float,free_float,critical\n\
+			A,2,0,2,0,2,0,0,true\n\
+			B,3,2,5,2,5,0,0,true\n\
+			C,1,2,3,4,5,2,2,false\n\
+			D,1,5,6,5,6,0,0,true\n"
+		);
+		let error = crate::csv::parse_str::<i32>(
+			"id,duration\nA,x\n,1\nB,-2\n",
+			&crate::csv::CsvColumns { predecessors: None, ..Default::default() }
+		).unwrap_err();
+		assert_eq!(
+			error,
+			"row 2, column 2: invalid duration: 'x'\n\
+			row 3, column 1: empty task ID\n\
+			row 4, column 2: invalid duration: '-2'"
+		);
+	}
+
+	#[cfg(feature = "mspdi")]
+	#[test]
+	fn cpm_mspdi_import_export() {
+		use crate::mspdi::{parse_str, write_project, MspdiOptions};
+		let options = MspdiOptions::default();
+		let tasks = parse_str::<i64>(r#"<?xml version="1.0"?>
+			<Project xmlns="http://schemas.microsoft.com/project">
+				<Tasks>
+					<Task><UID>0</UID><Name>Plan</Name><Summary>1</Summary></Task>
+					<Task><UID>1</UID><Name>Design &amp; spec</Name><Duration>PT16H0M0S</Duration></Task>
+					<Task>
+						<UID>2</UID><Name>Build</Name><Duration>PT40H0M0S</Duration>
+						<PredecessorLink><PredecessorUID>1</PredecessorUID><Type>1</Type></PredecessorLink>
+					</Task>
+					<Task>
+						<UID>3</UID><Name>Release</Name><Duration>PT0H0M0S</Duration><Milestone>1</Milestone>
+						<PredecessorLink><PredecessorUID>2</PredecessorUID><Type>3</Type><LinkLag>4800</LinkLag></PredecessorLink>
+						<PredecessorLink><PredecessorUID>0</PredecessorUID></PredecessorLink>
+					</Task>
+				</Tasks>
+			</Project>"#, &options).unwrap();
+		assert_eq!(tasks.len(), 3);
+		assert_eq!(tasks[0].get_metadata("name"), Some("Design & spec".to_string()));
+		assert_eq!(tasks[1].get_duration(), 5);
+		assert_eq!(tasks[2].get_dependencies(), vec!{"2".to_string()});
+		assert_eq!(tasks[2].get_metadata("milestone"), Some("true".to_string()));
+		assert_eq!(tasks[2].get_metadata("predecessor_links"), Some("2:SS+8h".to_string()));
+		assert!(parse_str::<i64>(
+			"<Project><Tasks><Task><UID>1</UID><Duration>PT4H0M0S</Duration></Task></Tasks></Project>"
+			, &options
+		).is_err());
+
+		let mut scheduler = Scheduler::<i64>::new();
+		scheduler.fill_tasklist(tasks).unwrap();
+		scheduler.schedule().unwrap();
+		let xml = write_project(&scheduler, &options).unwrap();
+		assert!(xml.contains("<Name>Design &amp; spec</Name>"));
+		assert!(!xml.contains("<Start>"));
+		let dated = write_project(&scheduler, &MspdiOptions {
+			start_date: Some("2024-03-01T08:00:00".to_string()),
+			..Default::default()
+		}).unwrap();
+		assert!(dated.contains("<Name>Design &amp; spec</Name>\n\t\t\t<Start>2024-03-01T08:00:00</Start>\n\t\t\t<Finish>2024-03-04T16:00:00</Finish>"));
+		assert!(dated.contains("<Name>Build</Name>\n\t\t\t<Start>2024-03-05T08:00:00</Start>\n\t\t\t<Finish>2024-03-11T16:00:00</Finish>"));
+		assert!(dated.contains("<Start>2024-03-12T08:00:00</Start>\n\t\t\t<Finish>2024-03-12T08:00:00</Finish>"));
+		assert!(xml.contains("<Type>3</Type>\n\t\t\t\t<LinkLag>4800</LinkLag>"));
+		let reparsed = parse_str::<i64>(&xml, &options).unwrap();
+		assert_eq!(reparsed[1].get_duration(), 5);
+		assert_eq!(reparsed[2].get_metadata("predecessor_links"), Some("2:SS+8h".to_string()));
+	}
+
+	#[test]
+	fn cpm_dot_export() {
+		use crate::dot::{DotCluster, DotOptions};
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("A".to_string(), 2, vec!{}),
+			CustomTask::new("B".to_string(), 3, vec!{"A".to_string()}),
+			CustomTask::new("C".to_string(), 1, vec!{"A".to_string()}),
+			CustomTask::new("X".to_string(), 1, vec!{}),
+		}).unwrap();
+		scheduler.schedule().unwrap();
+		let dot = scheduler.to_dot(&DotOptions::default());
+		assert!(dot.contains("\t\"B\" [label=\"B (3)\\nES 2 | EF 5\\nLS 2 | LF 5\\nTF 0\", color=red, penwidth=2];\n"));
+		assert!(dot.contains("\t\"A\" -> \"B\" [color=red, penwidth=2];\n"));
+		assert!(dot.contains("\t\"A\" -> \"C\";\n"));
+		let dot = scheduler.to_dot(&DotOptions {
+			left_to_right: true,
+			cluster: DotCluster::Component,
+			critical_only: true,
+		});
+		assert!(dot.contains("rankdir=LR;"));
+		assert!(dot.contains("subgraph cluster_0 {"));
+		assert!(!dot.contains("subgraph cluster_1 {"));
+		assert!(!dot.contains("\"C\""));
+	}
+
+	#[test]
+	fn cpm_svg_gantt() {
+		use crate::svg::{render_gantt, SvgOptions};
+		let mut scheduler = Scheduler::<f32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("A".to_string(), 2.0, vec!{}),
+			CustomTask::new("B".to_string(), 3.0, vec!{"A".to_string()}),
+			CustomTask::new("C".to_string(), 1.5, vec!{"A".to_string()}),
+			CustomTask::new("Done".to_string(), 0.0, vec!{"B".to_string(), "C".to_string()}),
+		}).unwrap();
+		assert!(render_gantt(&scheduler, &SvgOptions::default()).is_err());
+		scheduler.schedule().unwrap();
+		let svg = render_gantt(&scheduler, &SvgOptions {
+			title: Some("Release <1.0>".to_string()),
+			..Default::default()
+		}).unwrap();
+		assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+		assert!(svg.ends_with("</svg>\n"));
+		assert!(svg.contains("Release &lt;1.0&gt;"));
+		// B is critical, scale is 160 pixels per time unit.
+		assert!(svg.contains("<rect x=\"460\" y=\"88.8\" width=\"480\" height=\"14.4\" rx=\"2\" fill=\"#d9534f\">"));
+		// C has 1.5 float.
+		assert!(svg.contains("stroke=\"#9a9a9a\" stroke-dasharray=\"3,2\"/>"));
+		assert!(svg.contains("<polygon"));
+		assert_eq!(svg.matches("marker-end=\"url(#arrow)\"").count(), 4);
+	}
+
+	#[test]
+	fn cpm_terminal_gantt() {
+		use crate::terminal::{render_gantt, GanttSort, TerminalOptions};
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("A".to_string(), 4, vec!{}),
+			CustomTask::new("B".to_string(), 6, vec!{"A".to_string()}),
+			CustomTask::new("C".to_string(), 2, vec!{"A".to_string()}),
+			CustomTask::new("Done".to_string(), 0, vec!{"B".to_string(), "C".to_string()}),
+		}).unwrap();
+		scheduler.schedule().unwrap();
+		let options = TerminalOptions {
+			sort: GanttSort::Float,
+			scale: Some(0.5),
+			color: false,
+			unicode: false,
+			..Default::default()
+		};
+		assert_eq!(
+			render_gantt(&scheduler, &options).unwrap(),
+			"      0         5         10\n\
+			A    |########            |\n\
+			B    |        ############|\n\
+			Done |                   *|\n\
+			C    |        ####--------|\n"
+		);
+		let tiny = TerminalOptions { scale: Some(0.01), ..TerminalOptions::default() };
+		assert_eq!(
+			render_gantt(&scheduler, &tiny),
+			Err("Time scale 0.01 needs 1000 columns, only 73 fit the width: use a scale of at least 0.137.".to_string())
+		);
+		let colored = render_gantt(&scheduler, &TerminalOptions::default()).unwrap();
+		assert!(colored.contains("\x1b[31m"));
+		assert!(colored.contains("\x1b[2m"));
+	}
+
+	#[test]
+	fn cpm_mermaid_plantuml() {
+		use crate::diagram::{to_mermaid, to_plantuml, DiagramOptions};
+		use crate::date::Date;
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("A".to_string(), 4, vec!{}),
+			CustomTask::new("B-1".to_string(), 6, vec!{"A".to_string()}),
+			CustomTask::new("C".to_string(), 1, vec!{"A".to_string()}),
+			CustomTask::new("Done".to_string(), 0, vec!{"B-1".to_string(), "C".to_string()}),
+		}).unwrap();
+		scheduler.schedule().unwrap();
+		assert_eq!(
+			to_mermaid(&scheduler, &DiagramOptions::default()).unwrap(),
+			"gantt\n\
+			\tdateFormat X\n\
+			\taxisFormat %s\n\
+			\tA :crit, A, 0, 4s\n\
+			\tB-1 :crit, B_1, after A, 6s\n\
+			\tC :C, after A, 1s\n\
+			\tDone :crit, milestone, Done, after B_1 C, 0s\n"
+		);
+		let options = DiagramOptions {
+			start_date: Some("2024-02-27".parse::<Date>().unwrap()),
+			title: Some("Release".to_string()),
+		};
+		let mermaid = to_mermaid(&scheduler, &options).unwrap();
+		assert!(mermaid.contains("\tA :crit, A, 2024-02-27, 4d\n"));
+		let options = DiagramOptions { title: Some("Release: v2\nbeta".to_string()), ..options };
+		assert!(to_mermaid(&scheduler, &options).unwrap().starts_with("gantt\n\ttitle Release  v2 beta\n"));
+		let mut fractional = Scheduler::<f32>::new();
+		fractional.fill_tasklist(vec!{
+			CustomTask::new("A".to_string(), 1.5, vec!{}),
+			CustomTask::new("B".to_string(), 0.5, vec!{"A".to_string()}),
+		}).unwrap();
+		fractional.schedule().unwrap();
+		let mermaid = to_mermaid(&fractional, &options).unwrap();
+		assert!(mermaid.contains("\tA :crit, A, 2024-02-27, 1.5d\n\tB :crit, B, after A, 0.5d\n"));
+		assert_eq!(
+			to_plantuml(&scheduler, &DiagramOptions { title: Some("Release".to_string()), ..options.clone() }).unwrap(),
+			"@startgantt\n\
+			title Release\n\
+			Project starts 2024-02-27\n\
+			[A] as [A] requires 4 days\n\
+			[A] is colored in Red\n\
+			[B-1] as [B_1] requires 6 days\n\
+			[B_1] starts at [A]'s end\n\
+			[B_1] is colored in Red\n\
+			[C] as [C] requires 1 day\n\
+			[C] starts at [A]'s end\n\
+			[Done] as [Done] happens at [B_1]'s end\n\
+			[Done] is colored in Red\n\
+			@endgantt\n"
+		);
+		assert_eq!(options.start_date.unwrap().add_days(3).to_string(), "2024-03-01");
+		assert!("2023-02-29".parse::<Date>().is_err());
+		assert_eq!("4294967295-01-01".parse::<Date>(), Err("Year is out of range: 4294967295-01-01".to_string()));
+	}
+
+	#[test]
+	fn cpm_html_report() {
+		use crate::html::{render_report, HtmlOptions};
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("A".to_string(), 2, vec!{}),
+			CustomTask::new("B".to_string(), 3, vec!{"A".to_string()}),
+			CustomTask::new("C<x>".to_string(), 1, vec!{"A".to_string()}),
+		}).unwrap();
+		scheduler.schedule().unwrap();
+		assert_eq!(scheduler.get_concurrency_profile(), Ok(vec!{(0, 2, 1), (2, 3, 2), (3, 5, 1)}));
+		let html = render_report(&scheduler, &HtmlOptions::default()).unwrap();
+		assert!(html.starts_with("<!DOCTYPE html>"));
+		assert!(html.contains("<tr class=\"critical\"><td>B</td><td>B</td><td>3</td><td>2</td><td>5</td><td>2</td><td>5</td><td>0</td></tr>"));
+		assert!(html.contains("<tr><td>C&lt;x&gt;</td>"));
+		assert!(html.contains("<li>A &rarr; B (5)</li>"));
+		assert!(html.contains("<svg xmlns="));
+		assert!(!html.contains("src=\"http"));
+	}
+
+	#[test]
+	fn cpm_schedule_summary() {
+		use crate::summary::{MarkdownFormatter, ScheduleSummary, SummaryFormatter};
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("A".to_string(), 2, vec!{}),
+			CustomTask::new("B".to_string(), 3, vec!{"A".to_string()}),
+			CustomTask::new("C".to_string(), 1, vec!{"A".to_string()}),
+			CustomTask::new("D".to_string(), 1, vec!{"B".to_string(), "C".to_string()}),
+		}).unwrap();
+		assert!(ScheduleSummary::from_scheduler(&scheduler).is_err());
+		scheduler.schedule().unwrap();
+		let summary = ScheduleSummary::from_scheduler(&scheduler).unwrap();
+		assert_eq!(summary.duration, 6);
+		assert_eq!(summary.critical_task_count, 3);
+		assert_eq!(summary.float.as_ref().unwrap().max, 2);
+		assert_eq!(summary.float.as_ref().unwrap().mean, 0.5);
+		assert_eq!(
+			summary.to_string(),
+			"Project duration: 6\n\
+			Tasks: 4\n\
+			Critical tasks: 3\n\
+			Maximum parallel tasks: 2\n\
+			Start points: A\n\
+			End points: D\n\
+			Minimum float: 0\n\
+			Maximum float: 2\n\
+			Mean float: 0.5\n\
+			Tasks without float: 3\n\
+			Critical paths: 1\n\
+			\tA -> B -> D (6)\n"
+		);
+		let markdown = MarkdownFormatter.format(&summary).unwrap();
+		assert!(markdown.contains("| Project duration | 6 |\n"));
+		assert!(markdown.contains("1. A → B → D (6)\n"));
+		#[cfg(feature = "json")]
+		{
+			let json = crate::summary::JsonFormatter.format(&summary).unwrap();
+			let restored: ScheduleSummary<i32> = serde_json::from_str(&json).unwrap();
+			assert_eq!(restored, summary);
+		}
+	}
+
+	#[test]
+	fn cpm_dependency_checks() {
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("A".to_string(), 1, vec!{"C".to_string()}),
+			CustomTask::new("B".to_string(), 1, vec!{"A".to_string(), "X".to_string()}),
+			CustomTask::new("C".to_string(), 1, vec!{"B".to_string()}),
+			CustomTask::new("D".to_string(), 1, vec!{}),
+		}).unwrap();
+		assert_eq!(
+			scheduler.get_dangling_dependencies(),
+			vec!{("B".to_string(), "X".to_string())}
+		);
+		assert_eq!(
+			scheduler.find_cycle(),
+			Some(vec!{"B".to_string(), "C".to_string(), "A".to_string(), "B".to_string()})
+		);
+		assert_eq!(
+			scheduler.schedule(),
+			Err("Dependency cycle found: B -> C -> A -> B".to_string())
+		);
+		scheduler.get_mut_task_by_name(&"A".to_string()).unwrap().set_dependencies(vec!{});
+		assert_eq!(scheduler.find_cycle(), None);
+		assert_eq!(scheduler.schedule(), Ok(()));
+	}
+
+	#[test]
+	fn cpm_watch_diff() {
+		use std::time::Duration;
+		use crate::watch::{FileWatcher, ScheduleDiff};
+
+		let schedule = |tasks: Vec<CustomTask<i32>>| {
+			let mut scheduler = Scheduler::<i32>::new();
+			scheduler.fill_tasklist(tasks).unwrap();
+			scheduler.schedule().unwrap();
+			scheduler
+		};
+		let old = schedule(vec!{
+			CustomTask::new("A".to_string(), 2, vec!{}),
+			CustomTask::new("B".to_string(), 3, vec!{"A".to_string()}),
+			CustomTask::new("C".to_string(), 1, vec!{"A".to_string()}),
+			CustomTask::new("E".to_string(), 1, vec!{"B".to_string(), "C".to_string()}),
+		});
+		let new = schedule(vec!{
+			CustomTask::new("A".to_string(), 2, vec!{}),
+			CustomTask::new("B".to_string(), 3, vec!{"A".to_string()}),
+			CustomTask::new("C".to_string(), 5, vec!{"A".to_string()}),
+			CustomTask::new("D".to_string(), 1, vec!{}),
+			CustomTask::new("E".to_string(), 1, vec!{"B".to_string(), "C".to_string()}),
+		});
+		assert!(ScheduleDiff::between(&old, &old).unwrap().is_empty());
+		let diff = ScheduleDiff::between(&old, &new).unwrap();
+		assert_eq!(diff.new_duration, 8);
+		assert_eq!(diff.added_tasks, vec!{"D".to_string()});
+		assert_eq!(
+			diff.to_string(),
+			"Project duration: 6 -> 8 (+2)\n\
+			+ task D\n\
+			- critical: A -> B -> E\n\
+			+ critical: A -> C -> E\n\
+			~ float B: 0 -> 2\n\
+			~ float C: 2 -> 0\n"
+		);
+
+		let path = std::env::temp_dir().join(format!("cpm_watch_{}.tasks.in", std::process::id()));
+		let path_string = path.to_string_lossy().to_string();
+		std::fs::write(&path, "A(1)").unwrap();
+		let mut watcher = FileWatcher::new(vec!{path_string.clone()}, Duration::from_millis(10));
+		assert!(watcher.poll().is_empty());
+		std::fs::write(&path, "A(10)").unwrap();
+		assert_eq!(watcher.wait_for_change(), vec!{path_string.clone()});
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(watcher.poll(), vec!{path_string});
+
+		// The included files of the last read are watched too.
+		let directory = std::env::temp_dir().join(format!("cpm_watch_includes_{}", std::process::id()));
+		std::fs::create_dir_all(&directory).unwrap();
+		let root = directory.join("plan.tasks.in").to_string_lossy().to_string();
+		std::fs::write(&root, "include \"lib.tasks.in\" as lib\nB(1) after [lib.A]").unwrap();
+		std::fs::write(directory.join("lib.tasks.in"), "A(1)").unwrap();
+		let mut watcher = FileWatcher::new(vec!{root.clone()}, Duration::from_millis(10));
+		let contents = std::fs::read_to_string(&root).unwrap();
+		let parsed = crate::input_parser::parse_input_with::<i32>(&contents, Some(&root), &Default::default());
+		let mut paths = vec!{root.clone()};
+		paths.extend(parsed.sources.keys().cloned());
+		watcher.set_paths(paths.clone());
+		assert!(watcher.poll().is_empty());
+		std::fs::write(directory.join("lib.tasks.in"), "A(10)").unwrap();
+		assert_eq!(watcher.wait_for_change(), vec!{paths[1].clone()});
+		std::fs::remove_dir_all(&directory).unwrap();
+	}
+
+	#[test]
+	fn cpm_repl() {
+		use crate::input_parser::{parse_input, write_input};
+		use crate::repl::Repl;
+
+		let mut repl = Repl::<i64>::new(
+			parse_input("A(2)\nB(3) after [A]\nC(1) after [A]\nE(1) after [B, C]").unwrap()
+			, None
+		).unwrap();
+		assert_eq!(repl.execute("duration"), Ok("Project duration: 6\n".to_string()));
+		assert_eq!(
+			repl.execute("set C 5"),
+			Ok("Project duration: 6 -> 8 (+2)\n\
+				- critical: A -> B -> E\n\
+				+ critical: A -> C -> E\n\
+				~ float B: 0 -> 2\n\
+				~ float C: 2 -> 0\n".to_string())
+		);
+		// Shortening has to recalculate every value, not only the grown ones.
+		repl.execute("set C 1").unwrap();
+		assert_eq!(repl.execute("float C"), Ok("Total float: 2\nFree float: 2\n".to_string()));
+		repl.execute("add X 4 after A, C").unwrap();
+		assert_eq!(repl.execute("add Y 1 after Q"), Err("Unknown task: Q".to_string()));
+		assert_eq!(
+			repl.execute("add A 1"),
+			Err("Failed to add task: task ID is already added: A".to_string())
+		);
+		repl.execute("rm C").unwrap();
+		assert_eq!(
+			repl.get_scheduler().get_task_by_name(&"X".to_string()).unwrap().get_dependencies(),
+			vec!{"A".to_string()}
+		);
+		repl.execute("undo").unwrap();
+		assert_eq!(repl.execute("critical"), Ok("A -> C -> X (7)\n".to_string()));
+		assert_eq!(
+			write_input(&repl.get_scheduler().get_tasks()),
+			"A(2)\nB(3) after [A]\nC(1) after [A]\nE(1) after [B, C]\nX(4) after [A, C]\n"
+		);
+		// The undone removal puts the task back at it's place.
+		let order: Vec<String> = repl.get_scheduler().get_tasks_in_insertion_order().iter().map(|task| task.get_id()).collect();
+		assert_eq!(order, vec!{"A", "B", "C", "E", "X"});
+		repl.execute("undo").unwrap();
+		repl.execute("undo").unwrap();
+		repl.execute("undo").unwrap();
+		assert_eq!(repl.execute("undo"), Err("Nothing to undo.".to_string()));
+		assert_eq!(repl.get_scheduler().get_duration(), Ok(6));
+
+		let mut output: Vec<u8> = vec!{};
+		repl.run("set A 1\nbogus\nquit\nduration\n".as_bytes(), &mut output).unwrap();
+		assert_eq!(
+			String::from_utf8(output).unwrap(),
+			"> Project duration: 6 -> 5 (-1)\n\
+			> Error: Unknown command or wrong arguments: bogus, try help\n\
+			> "
+		);
+
+		let mut repl = Repl::<i64>::new(
+			parse_input("\"Spec sign-off\"(2)\n\"Design review (v2)\"(5) after [\"Spec sign-off\"]").unwrap()
+			, None
+		).unwrap();
+		repl.execute("set \"Design review (v2)\" 3").unwrap();
+		repl.execute("add \"Ship it\" 1 after \"Design review (v2)\", \"Spec sign-off\"").unwrap();
+		assert_eq!(repl.execute("critical"), Ok("Spec sign-off -> Design review (v2) -> Ship it (6)\n".to_string()));
+		assert_eq!(repl.execute("float \"Spec sign-off\""), Ok("Total float: 0\nFree float: 0\n".to_string()));
+		repl.execute("rm \"Ship it\"").unwrap();
+		assert_eq!(repl.execute("float \"Ship it"), Err("Unclosed string".to_string()));
+	}
+
+	#[cfg(feature = "tui")]
+	#[test]
+	fn cpm_tui() {
+		use ratatui::backend::TestBackend;
+		use ratatui::crossterm::event::KeyCode;
+		use ratatui::Terminal;
+		use crate::tui::TuiApp;
+
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(vec!{
+			CustomTask::new("A".to_string(), 2, vec!{}),
+			CustomTask::new("B".to_string(), 3, vec!{"A".to_string()}),
+			CustomTask::new("C".to_string(), 1, vec!{"A".to_string()}),
+			CustomTask::new("E".to_string(), 1, vec!{"B".to_string(), "C".to_string()}),
+		}).unwrap();
+		let mut app = TuiApp::new(scheduler, "plan".to_string()).unwrap();
+		let mut terminal = Terminal::new(TestBackend::new(60, 14)).unwrap();
+		let mut screen = |app: &mut TuiApp<i32>| -> Vec<String> {
+			terminal.draw(|frame| app.draw(frame)).unwrap();
+			let buffer = terminal.backend().buffer().clone();
+			(0..buffer.area.height)
+				.map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+				.collect()
+		};
+
+		let lines = screen(&mut app);
+		assert!(lines[0].starts_with("plan  duration 6  tasks 4  critical 3"));
+		assert!(lines.iter().any(|line| line.contains("> A ") && line.contains("█")));
+		assert!(lines.iter().any(|line| line.contains("Successors: B, C")));
+
+		app.handle_key(KeyCode::Down);
+		assert_eq!(app.get_selected(), Some("B".to_string()));
+		for key in [KeyCode::Char('e'), KeyCode::Char('5'), KeyCode::Enter] {
+			assert!(!app.handle_key(key));
+		}
+		assert_eq!(app.get_scheduler().get_duration(), Ok(8));
+		assert_eq!(app.get_selected(), Some("B".to_string()));
+		let lines = screen(&mut app);
+		assert!(lines.iter().any(|line| line.starts_with("B: 3 -> 5, project duration 8")));
+		assert!(lines.iter().any(|line| line.contains("Predecessors: A")));
+
+		// Canceled edits do not change anything.
+		for key in [KeyCode::Char('e'), KeyCode::Char('1'), KeyCode::Esc] {
+			app.handle_key(key);
+		}
+		assert_eq!(app.get_scheduler().get_duration(), Ok(8));
+
+		// Durations with units are converted, 16 hours are 2 days.
+		for key in [KeyCode::Char('e'), KeyCode::Char('1'), KeyCode::Char('6'), KeyCode::Char('h'), KeyCode::Enter] {
+			app.handle_key(key);
+		}
+		assert_eq!(app.get_scheduler().get_duration(), Ok(5));
+		app.set_units(crate::units::UnitConfig { base: crate::units::TimeUnit::Hour, ..Default::default() });
+		for key in [KeyCode::Char('e'), KeyCode::Char('1'), KeyCode::Char('d'), KeyCode::Enter] {
+			app.handle_key(key);
+		}
+		assert_eq!(app.get_scheduler().get_task_by_name(&"B".to_string()).unwrap().get_duration(), 8);
+		assert!(app.handle_key(KeyCode::Char('q')));
+	}
+
+	#[test]
+	fn cpm_input_grammar() {
+		use crate::input_parser::parse_input;
+
+		let tasks = parse_input(
+			"# Estimates from the kickoff meeting.\r\n\
+			A(10)   // design\r\n\
+			\t \r\n\
+			/* Backend work,\r\n   estimated by the API team. */\r\n\
+			B( 20 )after[A,]\r\n\
+			C(5) after [\r\n  A,\r\n  B, # waits for B\r\n]\r\n\
+			D(1) after [A B]"
+		).unwrap();
+		let summary: Vec<(String, i64, Vec<String>)> = tasks.iter()
+			.map(|task| (task.get_id(), task.get_duration(), task.get_dependencies()))
+			.collect();
+		assert_eq!(summary, vec!{
+			("A".to_string(), 10, vec!{}),
+			("B".to_string(), 20, vec!{"A".to_string()}),
+			("C".to_string(), 5, vec!{"A".to_string(), "B".to_string()}),
+			("D".to_string(), 1, vec!{"A".to_string(), "B".to_string()}),
+		});
+		assert_eq!(parse_input::<i64>("  \n# nothing\n").unwrap().len(), 0);
+
+		assert_eq!(
+			parse_input::<i64>("A(10)\nF(15) ater [A]").err(),
+			Some("line 2, column 7: unknown keyword 'ater', expected 'after'".to_string())
+		);
+		assert_eq!(
+			parse_input::<i64>("A(10)\r\nB(-1)").err(),
+			Some("line 2, column 3: negative duration: -1".to_string())
+		);
+		assert_eq!(
+			parse_input::<i64>("A(1) after [B,, C]").err(),
+			Some("line 1, column 15: expected dependency ID or ']', found ','".to_string())
+		);
+		assert_eq!(
+			parse_input::<i64>("A(1) after [B").err(),
+			Some("line 1, column 12: unclosed '['".to_string())
+		);
+		assert_eq!(
+			parse_input::<i64>("A(1)\n/* open").err(),
+			Some("line 2, column 1: unclosed block comment".to_string())
+		);
+
+		let tasks = parse_input::<f64>("A(2.5)\nB(1e1) after [A]").unwrap();
+		assert_eq!(tasks[0].get_duration(), 2.5);
+		assert_eq!(tasks[1].get_duration(), 10.0);
+		assert_eq!(
+			parse_input::<i32>("A(2.5)").err(),
+			Some("line 1, column 3: invalid duration: 2.5".to_string())
+		);
+		assert_eq!(
+			parse_input::<f32>("A(-0.5)").err(),
+			Some("line 1, column 3: negative duration: -0.5".to_string())
+		);
+	}
+
+	#[test]
+	fn cpm_input_diagnostics() {
+		use crate::input_parser::{parse_input_diagnostics, DiagnosticKind};
+
+		let source = "A(1)\nB(x) after [A]\nC(2) ater [A]\nD(-2)\nE(1) after [A\nA(4)\nF(3) after [A]";
+		let parsed = parse_input_diagnostics::<i64>(source);
+		let ids: Vec<String> = parsed.tasks.iter().map(|task| task.get_id()).collect();
+		assert_eq!(ids, vec!{"A".to_string(), "F".to_string()});
+		let kinds: Vec<(DiagnosticKind, usize, usize)> = parsed.diagnostics.iter()
+			.map(|diagnostic| (diagnostic.kind.clone(), diagnostic.line, diagnostic.column))
+			.collect();
+		assert_eq!(kinds, vec!{
+			(DiagnosticKind::InvalidDuration, 2, 3),
+			(DiagnosticKind::UnknownKeyword("ater".to_string()), 3, 6),
+			(DiagnosticKind::NegativeDuration, 4, 3),
+			(DiagnosticKind::UnclosedBracket, 5, 12),
+			(DiagnosticKind::DuplicateId("A".to_string()), 6, 1),
+		});
+		assert_eq!(&source[parsed.diagnostics[1].span.clone()], "ater");
+		assert_eq!(
+			parsed.diagnostics[1].render(source),
+			"error: unknown keyword 'ater', expected 'after'\n \
+			--> line 3, column 6\n  \
+			|\n\
+			3 | C(2) ater [A]\n  \
+			|      ^^^^\n"
+		);
+		assert_eq!(
+			parsed.diagnostics[4].message,
+			"duplicate task ID: A, first defined at line 1, column 1"
+		);
+		// An invalid ID at a task start must not stall the recovery.
+		let parsed = parse_input_diagnostics::<i64>("1e+5(1)\nB(2)");
+		assert_eq!(parsed.diagnostics.len(), 1);
+		assert_eq!(parsed.tasks.len(), 1);
+	}
+
+	#[test]
+	fn cpm_input_sources() {
+		use crate::input_parser::{parse_reader, parse_str, TaskList};
+
+		let tasks = parse_reader::<i64, _>("plan", "A(1)\nB(2) after [A]".as_bytes()).unwrap();
+		assert_eq!(tasks.len(), 2);
+		assert_eq!(
+			parse_str::<i64>("plan.tasks.in", "A(1)\nB(x)").err(),
+			Some("plan.tasks.in: line 2, column 3: invalid duration: x".to_string())
+		);
+		let list: TaskList<f64> = "A(1.5) B(2) after [A]".parse().unwrap();
+		assert_eq!(list.0[0].get_duration(), 1.5);
+		assert!("A(".parse::<TaskList<i64>>().is_err());
+	}
+
+	#[test]
+	fn cpm_input_attributes() {
+		use crate::input_parser::{parse_input, parse_input_diagnostics, write_input, DiagnosticKind};
+
+		let source = "A(10)\n\
+			B(20) after [A] { name: \"Backend \\\"v2\\\"\", resources: dev=2, tags: [api], estimate: 15..30 }\n\
+			C(0) after [B] {\n  milestone: true,\n  resources: [dev=1 qa=0.5],\n}";
+		let tasks = parse_input::<i64>(source).unwrap();
+		assert_eq!(tasks[1].get_dependencies(), vec!{"A".to_string()});
+		assert_eq!(tasks[1].get_metadata("name"), Some("Backend \"v2\"".to_string()));
+		assert_eq!(tasks[1].get_metadata("resources"), Some("dev=2".to_string()));
+		assert_eq!(tasks[1].get_metadata("tags"), Some("api".to_string()));
+		assert_eq!(tasks[1].get_metadata("estimate"), Some("15..30".to_string()));
+		assert_eq!(tasks[2].get_metadata("resources"), Some("dev=1, qa=0.5".to_string()));
+		let written = write_input(&tasks.iter().collect::<Vec<&CustomTask<i64>>>());
+		assert_eq!(
+			written,
+			"A(10)\n\
+			B(20) after [A] { name: \"Backend \\\"v2\\\"\", resources: [dev=2], tags: [api], estimate: 15..30 }\n\
+			C(0) after [B] { resources: [dev=1, qa=0.5], milestone: true }\n"
+		);
+		let reparsed = parse_input::<i64>(&written).unwrap();
+		assert_eq!(reparsed[1].get_metadata_map(), tasks[1].get_metadata_map());
+
+		let tasks = parse_input::<i64>("D(1) { resources: [\"dev team\"=2, qa=1] }").unwrap();
+		assert_eq!(tasks[0].get_metadata("resources"), Some("dev team=2, qa=1".to_string()));
+		let written = write_input(&tasks.iter().collect::<Vec<&CustomTask<i64>>>());
+		assert_eq!(written, "D(1) { resources: [\"dev team\"=2, qa=1] }\n");
+		assert_eq!(parse_input::<i64>(&written).unwrap()[0].get_metadata_map(), tasks[0].get_metadata_map());
+		let source = "E(1) { resources: [\"Smith, J.\"=1, qa=2], tags: [\"a, b\", \"c\\\\d\", e] }\n";
+		let tasks = parse_input::<i64>(source).unwrap();
+		assert_eq!(tasks[0].get_metadata("resources"), Some("Smith\\, J.=1, qa=2".to_string()));
+		assert_eq!(tasks[0].get_metadata("tags"), Some("a\\, b, c\\\\d, e".to_string()));
+		let written = write_input(&tasks.iter().collect::<Vec<&CustomTask<i64>>>());
+		assert_eq!(written, source);
+		assert_eq!(parse_input::<i64>(&written).unwrap()[0].get_metadata_map(), tasks[0].get_metadata_map());
+
+		let parsed = parse_input_diagnostics::<i64>(
+			"A(1) { owner: me }\nB(2) { estimate: 30..15 }\nC(3) { name: \"x }\nD(4)"
+		);
+		let kinds: Vec<DiagnosticKind> = parsed.diagnostics.iter().map(|diagnostic| diagnostic.kind.clone()).collect();
+		assert_eq!(kinds, vec!{
+			DiagnosticKind::UnknownAttribute("owner".to_string()),
+			DiagnosticKind::InvalidAttribute("estimate".to_string()),
+			DiagnosticKind::UnclosedString,
+			DiagnosticKind::UnexpectedToken,
+		});
+		assert_eq!(
+			parsed.diagnostics[0].to_string(),
+			"line 1, column 8: unknown attribute 'owner', expected one of: name, resources, tags, estimate, milestone"
+		);
+		assert_eq!(
+			parsed.diagnostics[1].to_string(),
+			"line 2, column 18: invalid value of 'estimate', expected MIN..MAX durations with MIN <= MAX"
+		);
+		assert_eq!(parsed.tasks.len(), 1);
+	}
+
+	#[test]
+	fn cpm_input_includes() {
+		use crate::input_parser::{parse_file_diagnostics, parse_input_file, DiagnosticKind};
+
+		let dir = std::env::temp_dir().join(format!("cpm_include_{}", std::process::id()));
+		std::fs::create_dir_all(dir.join("fe")).unwrap();
+		let plan = dir.join("plan.tasks.in").display().to_string();
+		std::fs::write(&plan, "include \"fe/frontend.tasks.in\" as fe\nA(2)\nB(3) after [A, fe.Build]").unwrap();
+		std::fs::write(dir.join("fe/frontend.tasks.in"), "Design(2)\nBuild(4) after [Design]").unwrap();
+		let tasks = parse_input_file::<i64>(&plan).unwrap();
+		let ids: Vec<String> = tasks.iter().map(|task| task.get_id()).collect();
+		assert_eq!(ids, vec!{"fe.Design", "fe.Build", "A", "B"});
+		assert_eq!(tasks[1].get_dependencies(), vec!{"fe.Design".to_string()});
+
+		std::fs::write(
+			dir.join("fe/frontend.tasks.in"),
+			"Design(x)\ninclude \"../plan.tasks.in\" as again"
+		).unwrap();
+		let contents = std::fs::read_to_string(&plan).unwrap();
+		let parsed = parse_file_diagnostics::<i64>(&plan, &contents);
+		let included = dir.join("fe/frontend.tasks.in").display().to_string();
+		let found: Vec<(DiagnosticKind, Option<String>, usize)> = parsed.diagnostics.iter()
+			.map(|diagnostic| (diagnostic.kind.clone(), diagnostic.source_name.clone(), diagnostic.line))
+			.collect();
+		assert_eq!(found, vec!{
+			(DiagnosticKind::InvalidDuration, Some(included.clone()), 1),
+			(DiagnosticKind::IncludeCycle, Some(included.clone()), 2),
+		});
+		assert!(parsed.render_diagnostics(&contents)[0].contains("1 | Design(x)"));
+		std::fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn cpm_input_writer() {
+		use crate::input_parser::{
+			parse_input, parse_input_diagnostics, write_parsed, write_scheduler, write_tasks, TaskOrder, WriteOptions
+		};
+
+		let source = "# Backend\nC(5) after [B] // last\nA(1)\n/* API\n   team */\nB(2) after [A] { tags: [api] }";
+		let tasks = parse_input::<i64>(source).unwrap();
+		assert_eq!(tasks[0].get_source("comment"), Some("Backend".to_string()));
+		assert_eq!(tasks[0].get_source("line_comment"), Some("last".to_string()));
+		assert_eq!(tasks[2].get_source("comment"), Some("API\nteam".to_string()));
+		assert!(tasks[0].get_metadata_map().is_empty());
+		let task_refs: Vec<&CustomTask<i64>> = tasks.iter().collect();
+		let topological = WriteOptions { order: TaskOrder::Topological, ..WriteOptions::default() };
+		let written = write_tasks(&task_refs, &topological);
+		assert_eq!(written, "A(1)\n\n# API\n# team\nB(2) after [A] { tags: [api] }\n\n# Backend\nC(5) after [B]  # last\n");
+		let reparsed = parse_input::<i64>(&written).unwrap();
+		let reparsed_refs: Vec<&CustomTask<i64>> = reparsed.iter().collect();
+		assert_eq!(write_tasks(&reparsed_refs, &topological), written);
+
+		let parsed = parse_input_diagnostics::<i64>("A(1)  # first\n\n# trailing comment\n/* at end */\n");
+		assert_eq!(parsed.trailing_comment, Some("trailing comment\nat end".to_string()));
+		let written = write_parsed(&parsed, &WriteOptions::default());
+		assert_eq!(written, "A(1)  # first\n\n# trailing comment\n# at end\n");
+		assert_eq!(write_parsed(&parse_input_diagnostics::<i64>(&written), &WriteOptions::default()), written);
+		let parsed = parse_input_diagnostics::<i64>("// nothing planned yet\n");
+		assert_eq!(write_parsed(&parsed, &WriteOptions::default()), "# nothing planned yet\n");
+
+		let mut scheduler = Scheduler::<i64>::new();
+		scheduler.add_task(CustomTask::new("Z".to_string(), 1, vec!{})).unwrap();
+		scheduler.add_task(CustomTask::new(
+			"Y".to_string(), 2, vec!{"Z".to_string(), "Dependency_with_a_long_name".to_string()}
+		)).unwrap();
+		let options = WriteOptions { width: 30, ..WriteOptions::default() };
+		assert_eq!(
+			write_scheduler(&scheduler, &options),
+			"Z(1)\nY(2) after [\n  Z,\n  Dependency_with_a_long_name,\n]\n"
+		);
+		let alphabetical = WriteOptions { order: TaskOrder::Alphabetical, width: 0, ..WriteOptions::default() };
+		assert_eq!(
+			write_scheduler(&scheduler, &alphabetical),
+			"Y(2) after [Z, Dependency_with_a_long_name]\nZ(1)\n"
+		);
+	}
+
+	#[test]
+	fn cpm_duration_units() {
+		use crate::input_parser::{
+			parse_input, parse_input_with, write_input, write_parsed, ParseOptions, WriteOptions
+		};
+		use crate::units::{TimeUnit, UnitConfig, UnitDuration};
+
+		let source = "A(3d)\nB(4h) after [A] { estimate: 2h..1d }\nC(2w) after [B]\nD(1.5)";
+		let mut tasks = parse_input::<f64>(source).unwrap();
+		let durations: Vec<f64> = tasks.iter().map(|task| task.get_duration()).collect();
+		assert_eq!(durations, vec!{3.0, 0.5, 10.0, 1.5});
+		assert_eq!(tasks[1].get_metadata("estimate"), Some("0.25..1".to_string()));
+		// The units are written back as long as the values match.
+		assert_eq!(write_input(&tasks.iter().collect::<Vec<&CustomTask<f64>>>()), format!("{}\n", source));
+		tasks[0].set_duration(4.0);
+		assert!(write_input(&tasks.iter().collect::<Vec<&CustomTask<f64>>>()).starts_with("A(4)\nB(4h)"));
+		let hours = UnitConfig { base: TimeUnit::Hour, ..UnitConfig::default() };
+		let parsed = parse_input_with::<f64>(source, None, &ParseOptions { units: hours, ..ParseOptions::default() });
+		assert_eq!(parsed.tasks[0].get_duration(), 24.0);
+		assert_eq!(write_parsed(&parsed, &WriteOptions { units: hours, ..WriteOptions::default() }), format!("{}\n", source));
+		// The source text and comments are not exported as metadata.
+		let parsed = parse_input::<f64>("# Backend\nA(3d)  # last\nB(4h) after [A] { estimate: 2h..1d }").unwrap();
+		assert!(parsed[0].get_metadata_map().is_empty());
+		#[cfg(feature = "json")]
+		{
+			let project = crate::project::ProjectFile::from_tasks(&parsed);
+			assert_eq!(project.tasks[1].metadata.keys().collect::<Vec<&String>>(), vec!{"estimate"});
+			let mut scheduler = Scheduler::<f64>::new();
+			for task in parsed {
+				scheduler.add_task(task).unwrap();
+			}
+			let json = crate::json::write_project(&scheduler).unwrap();
+			assert!(!json.contains("comment") && !json.contains("text") && !json.contains("4h"));
+		}
+		let shallow = ParseOptions { follow_includes: false, ..ParseOptions::default() };
+		let parsed = parse_input_with::<f64>("include \"a.tasks.in\" as a\nB(4h) after [a.A]", None, &shallow);
+		assert_eq!(write_parsed(&parsed, &WriteOptions::default()), "include \"a.tasks.in\" as a\n\nB(4h) after [a.A]\n");
+		assert_eq!(
+			parse_input::<i64>("A(3d)\nB(4h)").err(),
+			Some("line 2, column 3: invalid duration: 4h is 0.5d".to_string())
+		);
+
+		let units = UnitConfig { base: TimeUnit::Hour, hours_per_day: 7.5, days_per_week: 4.0 };
+		let options = ParseOptions { units, ..ParseOptions::default() };
+		let parsed = parse_input_with::<i64>("A(3d)\nB(4)\nC(1w)\nD(2x)", None, &options);
+		let durations: Vec<i64> = parsed.tasks.iter().map(|task| task.get_duration()).collect();
+		assert_eq!(durations, vec!{4, 30});
+		assert_eq!(parsed.diagnostics[0].message, "invalid duration: 3d is 22.5h");
+		assert_eq!(parsed.diagnostics[1].message, "invalid duration: 2x");
+
+		let duration: UnitDuration = "2.5w".parse().unwrap();
+		assert_eq!(duration, UnitDuration::weeks(2.5));
+		assert_eq!(duration.to_string(), "2.5w");
+		assert_eq!(duration.in_unit(TimeUnit::Day, &UnitConfig::default()), 12.5);
+		assert_eq!(UnitConfig::default().convert::<i64>(UnitDuration::hours(16.0)), Ok(2));
+		assert!("3".parse::<UnitDuration>().is_err());
+	}
+
+	#[test]
+	fn cpm_input_identifiers() {
+		use crate::input_parser::{parse_input, write_input};
+
+		let source = "\"Spec sign-off\"(2)\n\
+			\"Design review (v2)\"(5) after [\"Spec sign-off\"]\n\
+			Tervezés(3) after [\"Design review (v2)\", \"Spec sign-off\"]\n\
+			\"say \\\"hi\\\"\"(1) after [Tervezés]\n\
+			\"after\"(1) after [\"say \\\"hi\\\"\"]";
+		let tasks = parse_input::<i64>(source).unwrap();
+		let ids: Vec<String> = tasks.iter().map(|task| task.get_id()).collect();
+		assert_eq!(ids, vec!{"Spec sign-off", "Design review (v2)", "Tervezés", "say \"hi\"", "after"});
+		assert_eq!(tasks[2].get_dependencies(), vec!{"Design review (v2)".to_string(), "Spec sign-off".to_string()});
+		let written = write_input(&tasks.iter().collect::<Vec<&CustomTask<i64>>>());
+		assert_eq!(written, format!("{}\n", source));
+		let reparsed = parse_input::<i64>(&written).unwrap();
+		let reparsed_ids: Vec<String> = reparsed.iter().map(|task| task.get_id()).collect();
+		assert_eq!(reparsed_ids, ids);
+		assert!(parse_input::<i64>("\"\"(1)").is_err());
+		// `+` only continues a word as the sign of an exponent.
+		assert_eq!(parse_input::<f64>("A(1e+1)").unwrap()[0].get_duration(), 10.0);
+		assert_eq!(
+			parse_input::<i64>("A+B(1)").err(),
+			Some("line 1, column 2: expected '(' after the task ID, found '+'".to_string())
+		);
+	}
+
+	#[cfg(all(feature = "yaml", feature = "toml"))]
+	#[test]
+	fn cpm_project_formats() {
+		let tasks = crate::yaml::parse_str::<i32>(concat!(
+			"tasks:\n",
+			"  - id: A\n",
+			"    duration: 2\n",
+			"  - id: B\n",
+			"    duration: 3\n",
+			"    dependencies: [A]\n",
+			"    metadata: {owner: me}\n",
+		)).unwrap();
+		assert_eq!(tasks[1].get_dependencies(), vec!{"A".to_string()});
+		assert_eq!(tasks[1].get_metadata("owner"), Some("me".to_string()));
+		let mut scheduler = Scheduler::<i32>::new();
+		scheduler.fill_tasklist(tasks).unwrap();
+		scheduler.schedule().unwrap();
+
+		let yaml = crate::yaml::write_project(&scheduler).unwrap();
+		let toml = crate::toml::write_project(&scheduler).unwrap();
+		for tasks in [crate::yaml::parse_str::<i32>(&yaml).unwrap(), crate::toml::parse_str::<i32>(&toml).unwrap()] {
+			let summary: Vec<(String, i32, Vec<String>)> = tasks.iter()
+				.map(|task| (task.get_id(), task.get_duration(), task.get_dependencies()))
+				.collect();
+			assert_eq!(summary, vec!{
+				("A".to_string(), 2, vec!{}),
+				("B".to_string(), 3, vec!{"A".to_string()}),
+			});
+			assert_eq!(tasks[1].get_metadata("owner"), Some("me".to_string()));
+		}
+
+		assert_eq!(
+			crate::toml::parse_str::<i32>("[[tasks]]\nid = \"A\"\nduration = \"x\"\n").err(),
+			Some("Invalid TOML project: line 3, column 12: invalid type: string \"x\", expected i32".to_string())
+		);
+		let error = crate::yaml::parse_str::<i32>("tasks:\n  - id: A\n    duratio: 1\n").unwrap_err();
+		assert!(error.starts_with("Invalid YAML project: line 3, column 5: "), "{}", error);
+		assert!(crate::yaml::parse_str::<i32>("tasks:\n  - id: A\n    duration: -1\n").is_err());
+	}
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::customtask::CustomTask;
+use crate::project::ProjectFile;
+use crate::scheduler::Scheduler;
+
+/// Parses a TOML project definition, the same schema as the JSON one with
+/// the tasks as `[[tasks]]` tables. Errors start with their line and column.
+pub fn parse_str<T>(contents: &str) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ DeserializeOwned
+{
+	let project: ProjectFile<T> = ::toml::from_str(contents).map_err(|e| {
+		let message = e.message().trim_end();
+		match e.span() {
+			Some(span) => {
+				let before = &contents[..span.start.min(contents.len())];
+				format!(
+					"Invalid TOML project: line {}, column {}: {}"
+					, before.matches('\n').count() + 1
+					, before.rsplit('\n').next().unwrap_or("").chars().count() + 1
+					, message
+				)
+			},
+			None => format!("Invalid TOML project: {}", message),
+		}
+	})?;
+	project.into_tasks()
+}
+
+/// Reads and parses a TOML project file.
+pub fn parse_file<T>(filename: &String) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ DeserializeOwned
+{
+	match fs::read_to_string(filename) {
+		Ok(contents) => parse_str(&contents)
+			.map_err(|e| format!("{}: {}", filename, e)),
+		Err(e) => Err(format!("Could not read file: {}\n\r{}", filename, e)),
+	}
+}
+
+/// Writes the tasks of a scheduler as a TOML project definition.
+pub fn write_project<T>(scheduler: &Scheduler<T>) -> Result<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ Serialize
+{
+	::toml::to_string_pretty(&ProjectFile::from_scheduler(scheduler))
+		.map_err(|e| format!("Could not write TOML project: {}", e))
+}
@@ -0,0 +1,407 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+
+use crate::numeric::{format_number, to_f64};
+use crate::scheduler::Scheduler;
+use crate::svg::is_milestone;
+use crate::terminal::timeline_cells;
+use crate::units::UnitConfig;
+
+static HELP: &str = "↑↓ select  ←→ scroll  +/- zoom  0 fit  e edit duration  q quit";
+
+/// State of the full-screen Gantt viewer.
+///
+/// Drawing and key handling are separated from the terminal, so the viewer
+/// can be driven by any ratatui backend.
+pub struct TuiApp<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	scheduler: Scheduler<T>,
+	title: String,
+	/// Task IDs in the shown order: by early start, then by ID.
+	order: Vec<String>,
+	critical_ids: HashSet<String>,
+	table_state: TableState,
+	/// Time units per character, `None` fits the whole project.
+	zoom: Option<f64>,
+	/// Time at the left edge of the timeline.
+	offset: f64,
+	/// Scale and width of the last drawn timeline, used for scrolling.
+	last_scale: f64,
+	last_timeline_width: usize,
+	/// Text of the new duration while editing.
+	editing: Option<String>,
+	message: Option<String>,
+	/// Conversion of the edited durations with units, like `3d`.
+	units: UnitConfig,
+}
+
+impl <T> TuiApp<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	/// Calculates the schedule and selects the first task.
+	pub fn new(mut scheduler: Scheduler<T>, title: String) -> Result<Self, String> {
+		scheduler.calculate()?;
+		let mut app = TuiApp {
+			scheduler,
+			title,
+			order: vec!{},
+			critical_ids: HashSet::new(),
+			table_state: TableState::default(),
+			zoom: None,
+			offset: 0.0,
+			last_scale: 1.0,
+			last_timeline_width: 0,
+			editing: None,
+			message: None,
+			units: UnitConfig::default(),
+		};
+		app.refresh()?;
+		if !app.order.is_empty() {
+			app.table_state.select(Some(0));
+		}
+		Ok(app)
+	}
+
+	/// Sets the conversion of the edited durations with units.
+	pub fn set_units(&mut self, units: UnitConfig) {
+		self.units = units;
+	}
+
+	pub fn get_scheduler(&self) -> &Scheduler<T> {
+		&self.scheduler
+	}
+
+	/// ID of the selected task.
+	pub fn get_selected(&self) -> Option<String> {
+		self.table_state.selected().and_then(|row| self.order.get(row).cloned())
+	}
+
+	/// Handles a key press, returns true if the viewer should quit.
+	pub fn handle_key(&mut self, key: KeyCode) -> bool {
+		if self.editing.is_some() {
+			self.handle_edit_key(key);
+			return false;
+		}
+		self.message = None;
+		let page = (self.last_timeline_width as f64 / 4.0).max(1.0) * self.last_scale;
+		match key {
+			KeyCode::Char('q') | KeyCode::Esc => { return true; },
+			KeyCode::Down | KeyCode::Char('j') => self.table_state.select_next(),
+			KeyCode::Up | KeyCode::Char('k') => self.table_state.select_previous(),
+			KeyCode::Home => self.table_state.select_first(),
+			KeyCode::End => self.table_state.select_last(),
+			KeyCode::Right | KeyCode::Char('l') => { self.offset += page; },
+			KeyCode::Left | KeyCode::Char('h') => { self.offset = (self.offset - page).max(0.0); },
+			KeyCode::Char('+') => { self.zoom = Some(self.last_scale / 2.0); },
+			KeyCode::Char('-') => { self.zoom = Some(self.last_scale * 2.0); },
+			KeyCode::Char('0') => {
+				self.zoom = None;
+				self.offset = 0.0;
+			},
+			KeyCode::Char('e') | KeyCode::Enter if self.get_selected().is_some() => {
+				self.editing = Some(String::new());
+			},
+			_ => {},
+		}
+		false
+	}
+
+	/// Draws the header, the task table with the timeline, the details
+	/// of the selected task and the status line.
+	pub fn draw(&mut self, frame: &mut Frame) {
+		let [header_area, table_area, details_area, status_area] = Layout::vertical([
+			Constraint::Length(1),
+			Constraint::Min(3),
+			Constraint::Length(4),
+			Constraint::Length(1),
+		]).areas(frame.area());
+
+		let duration = self.scheduler.get_duration().map(|d| d.to_string()).unwrap_or_default();
+		frame.render_widget(Paragraph::new(Line::from(vec!{
+			Span::styled(self.title.clone(), Style::default().add_modifier(Modifier::BOLD)),
+			Span::raw(format!(
+				"  duration {}  tasks {}  critical {}"
+				, duration
+				, self.order.len()
+				, self.critical_ids.len()
+			)),
+		})), header_area);
+
+		self.draw_table(frame, table_area);
+		self.draw_details(frame, details_area);
+
+		let selected = self.get_selected().and_then(|id| self.scheduler.get_task_by_name(&id));
+		let status = match (&self.editing, &self.message, selected) {
+			(Some(text), _, Some(task)) => format!(
+				"New duration of {} (now {}): {}▏ Enter apply, Esc cancel"
+				, task.get_id()
+				, task.get_duration()
+				, text
+			),
+			(None, Some(message), _) => message.clone(),
+			_ => HELP.to_string(),
+		};
+		frame.render_widget(Paragraph::new(status), status_area);
+	}
+
+	fn draw_table(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
+		let value = |value: Option<T>| value.map_or("-".to_string(), |v| v.to_string());
+		let headers = ["ID", "DUR", "ES", "EF", "LF", "TF"];
+		let mut texts: Vec<[String; 6]> = vec!{};
+		for id in &self.order {
+			if let Some(task) = self.scheduler.get_task_by_name(id) {
+				texts.push([
+					task.get_id(),
+					task.get_duration().to_string(),
+					value(task.get_early_start()),
+					value(task.get_early_finish()),
+					value(task.get_late_finish()),
+					value(task.get_total_float().ok()),
+				]);
+			}
+		}
+		let widths: Vec<usize> = (0..headers.len())
+			.map(|column| texts.iter()
+				.map(|row| row[column].chars().count())
+				.chain(std::iter::once(headers[column].len()))
+				.max()
+				.unwrap_or(0))
+			.collect();
+		// Borders, the highlight symbol and the spaces between the columns.
+		let used = 2 + 2 + widths.iter().sum::<usize>() + widths.len();
+		let timeline_width = (area.width as usize).saturating_sub(used).max(1);
+		let duration = to_f64(self.scheduler.get_duration().unwrap_or(0.into()));
+		let scale = match self.zoom {
+			Some(zoom) => zoom,
+			None if duration > 0.0 => duration / timeline_width as f64,
+			None => 1.0,
+		};
+		self.last_scale = scale;
+		self.last_timeline_width = timeline_width;
+
+		let selected = self.get_selected().and_then(|id| self.scheduler.get_task_by_name(&id));
+		let predecessors: Vec<String> = selected
+			.map(|task| task.get_dependencies())
+			.unwrap_or_default();
+		let successors: Vec<String> = selected
+			.map(|task| self.scheduler.get_task_successors(task).iter().map(|t| t.get_id()).collect())
+			.unwrap_or_default();
+
+		let mut rows: Vec<Row> = vec!{};
+		for (id, text) in self.order.iter().zip(texts) {
+			let task = match self.scheduler.get_task_by_name(id) {
+				Some(task) => task,
+				None => { continue; },
+			};
+			let critical = self.critical_ids.contains(id);
+			let cells = timeline_cells(
+				(
+					to_f64(task.get_early_start().unwrap_or(0.into())),
+					to_f64(task.get_early_finish().unwrap_or(0.into())),
+					to_f64(task.get_late_finish().unwrap_or(0.into())),
+				)
+				, is_milestone(task)
+				, self.offset
+				, scale
+				, timeline_width
+				, ('█', '░', '◆')
+			);
+			let id_style = if predecessors.contains(id) {
+				Style::default().fg(Color::Cyan)
+			} else if successors.contains(id) {
+				Style::default().fg(Color::Yellow)
+			} else {
+				Style::default()
+			};
+			let mut row_cells: Vec<Cell> = text.into_iter()
+				.enumerate()
+				.map(|(column, text)| if column == 0 {
+					Cell::from(text).style(id_style)
+				} else {
+					Cell::from(text)
+				})
+				.collect();
+			row_cells.push(Cell::from(cells.into_iter().collect::<String>()));
+			let style = if critical {
+				Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+			} else {
+				Style::default()
+			};
+			rows.push(Row::new(row_cells).style(style));
+		}
+
+		let mut constraints: Vec<Constraint> = widths.iter()
+			.map(|width| Constraint::Length(*width as u16))
+			.collect();
+		constraints.push(Constraint::Min(1));
+		let mut header_cells: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+		header_cells.push(format!(
+			"{} .. {}"
+			, format_number(self.offset)
+			, format_number(self.offset + scale * timeline_width as f64)
+		));
+		let table = Table::new(rows, constraints)
+			.header(Row::new(header_cells).style(Style::default().add_modifier(Modifier::UNDERLINED)))
+			.block(Block::default().borders(Borders::ALL).title("Tasks"))
+			.row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+			.highlight_symbol("> ");
+		frame.render_stateful_widget(table, area, &mut self.table_state);
+	}
+
+	fn draw_details(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+		let mut lines: Vec<Line> = vec!{};
+		let selected = self.get_selected().and_then(|id| self.scheduler.get_task_by_name(&id));
+		if let Some(task) = selected {
+			let value = |value: Option<T>| value.map_or("-".to_string(), |v| v.to_string());
+			lines.push(Line::from(format!(
+				"{}  duration {}  ES {}  EF {}  LS {}  LF {}  TF {}  FF {}{}"
+				, task.get_id()
+				, task.get_duration()
+				, value(task.get_early_start())
+				, value(task.get_early_finish())
+				, value(task.get_late_start())
+				, value(task.get_late_finish())
+				, value(task.get_total_float().ok())
+				, value(self.scheduler.get_free_float(task).ok())
+				, if self.critical_ids.contains(&task.get_id()) { "  critical" } else { "" }
+			)));
+			let successors: Vec<String> = self.scheduler.get_task_successors(task).iter()
+				.map(|successor| successor.get_id())
+				.collect::<std::collections::BTreeSet<String>>()
+				.into_iter()
+				.collect();
+			lines.push(Line::from(vec!{
+				Span::styled("Predecessors: ", Style::default().fg(Color::Cyan)),
+				Span::raw(task.get_dependencies().join(", ")),
+			}));
+			lines.push(Line::from(vec!{
+				Span::styled("Successors: ", Style::default().fg(Color::Yellow)),
+				Span::raw(successors.join(", ")),
+			}));
+		}
+		frame.render_widget(
+			Paragraph::new(lines).block(Block::default().borders(Borders::TOP))
+			, area
+		);
+	}
+
+	fn handle_edit_key(&mut self, key: KeyCode) {
+		let text = match self.editing.as_mut() {
+			Some(text) => text,
+			None => { return; },
+		};
+		match key {
+			KeyCode::Char(c) if c.is_ascii_digit() || ".hdw".contains(c) => text.push(c),
+			KeyCode::Backspace => { text.pop(); },
+			KeyCode::Esc => { self.editing = None; },
+			KeyCode::Enter => {
+				let text = text.clone();
+				self.editing = None;
+				if let Err(e) = self.set_selected_duration(&text) {
+					self.message = Some(format!("Error: {}", e));
+				}
+			},
+			_ => {},
+		}
+	}
+
+	/// Sets the duration of the selected task and reschedules.
+	/// Durations with a unit are converted like in the input files.
+	/// The old duration is restored if the calculation fails.
+	fn set_selected_duration(&mut self, text: &str) -> Result<(), String> {
+		let duration = match self.units.parse::<T>(text) {
+			Ok(duration) if duration >= 0.into() => duration,
+			_ => { return Err(format!("Invalid duration: {}", text)); },
+		};
+		let id = self.get_selected().ok_or("No task is selected.")?;
+		let task = self.scheduler.get_mut_task_by_name(&id).ok_or(format!("Unknown task: {}", id))?;
+		let old_duration = task.get_duration();
+		task.set_duration(duration);
+		if let Err(e) = self.scheduler.calculate() {
+			if let Some(task) = self.scheduler.get_mut_task_by_name(&id) {
+				task.set_duration(old_duration);
+			}
+			self.scheduler.calculate()?;
+			return Err(e);
+		}
+		self.refresh()?;
+		let row = self.order.iter().position(|task_id| *task_id == id);
+		self.table_state.select(row);
+		self.message = Some(format!(
+			"{}: {} -> {}, project duration {}"
+			, id
+			, old_duration
+			, duration
+			, self.scheduler.get_duration()?
+		));
+		Ok(())
+	}
+
+	/// Updates the order and the critical tasks after a calculation.
+	fn refresh(&mut self) -> Result<(), String> {
+		let mut tasks = self.scheduler.get_tasks();
+		tasks.sort_by(|a, b| a.get_early_start().partial_cmp(&b.get_early_start())
+			.unwrap_or(std::cmp::Ordering::Equal));
+		self.order = tasks.iter().map(|task| task.get_id()).collect();
+		self.critical_ids = self.scheduler.get_critical_task_ids()?;
+		Ok(())
+	}
+}
+
+/// Runs the viewer in the terminal until the user quits.
+/// `units` converts the edited durations with units.
+pub fn run<T>(scheduler: Scheduler<T>, title: String, units: UnitConfig) -> Result<(), String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ FromStr
+{
+	let mut app = TuiApp::new(scheduler, title)?;
+	app.set_units(units);
+	let mut terminal = ratatui::init();
+	let result = loop {
+		if let Err(e) = terminal.draw(|frame| app.draw(frame)) {
+			break Err(format!("Could not draw: {}", e));
+		}
+		match event::read() {
+			Ok(Event::Key(key)) if key.kind == KeyEventKind::Press && app.handle_key(key.code) => {
+				break Ok(());
+			},
+			Ok(_) => {},
+			Err(e) => { break Err(format!("Could not read event: {}", e)); },
+		}
+	};
+	ratatui::restore();
+	result
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Units of the durations in the input files.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeUnit {
+	Hour,
+	Day,
+	Week,
+}
+
+/// Parses `h`, `d` or `w`.
+impl FromStr for TimeUnit {
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err> {
+		match text {
+			"h" => Ok(TimeUnit::Hour),
+			"d" => Ok(TimeUnit::Day),
+			"w" => Ok(TimeUnit::Week),
+			_ => Err(format!("Unknown time unit: {}, expected h, d or w", text)),
+		}
+	}
+}
+
+/// `h`, `d` or `w`.
+impl fmt::Display for TimeUnit {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			TimeUnit::Hour => write!(f, "h"),
+			TimeUnit::Day => write!(f, "d"),
+			TimeUnit::Week => write!(f, "w"),
+		}
+	}
+}
+
+/// Conversion of the duration units into the time units of the scheduler.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnitConfig {
+	/// Unit of the scheduler times and of the durations without a unit.
+	pub base: TimeUnit,
+	/// Working hours of a day, positive.
+	pub hours_per_day: f64,
+	/// Working days of a week, positive.
+	pub days_per_week: f64,
+}
+
+/// Days of 8 hours and weeks of 5 days, in days.
+impl Default for UnitConfig {
+	fn default() -> Self {
+		UnitConfig {
+			base: TimeUnit::Day,
+			hours_per_day: 8.0,
+			days_per_week: 5.0,
+		}
+	}
+}
+
+impl UnitConfig {
+	/// Length of a unit in hours.
+	pub fn hours(&self, unit: TimeUnit) -> f64 {
+		match unit {
+			TimeUnit::Hour => 1.0,
+			TimeUnit::Day => self.hours_per_day,
+			TimeUnit::Week => self.hours_per_day * self.days_per_week,
+		}
+	}
+
+	/// Converts a duration into the base unit.
+	pub fn to_base(&self, duration: UnitDuration) -> f64 {
+		duration.value * self.hours(duration.unit) / self.hours(self.base)
+	}
+
+	/// Converts a duration into the time type of the scheduler.
+	/// Fails if the type can not hold the value, like 0.5 days in integers.
+	pub fn convert<T>(&self, duration: UnitDuration) -> Result<T, String>
+	where T: FromStr
+	{
+		let value = self.to_base(duration);
+		value.to_string().parse::<T>()
+			.map_err(|_| format!("{} is {}{}, not a valid duration", duration, value, self.base))
+	}
+
+	/// Parses a duration with an optional unit suffix, like `3d`, `4h` or `2.5`.
+	/// Numbers without a unit are in the base unit.
+	pub fn parse<T>(&self, text: &str) -> Result<T, String>
+	where T: FromStr
+	{
+		match text.parse::<UnitDuration>() {
+			Ok(duration) => self.convert(duration),
+			Err(_) => text.parse::<T>().map_err(|_| format!("Invalid duration: {}", text)),
+		}
+	}
+}
+
+/// Duration with it's unit, like `3d`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnitDuration {
+	value: f64,
+	unit: TimeUnit,
+}
+
+impl UnitDuration {
+	pub fn new(value: f64, unit: TimeUnit) -> Self {
+		UnitDuration { value, unit }
+	}
+
+	pub fn hours(value: f64) -> Self {
+		UnitDuration::new(value, TimeUnit::Hour)
+	}
+
+	pub fn days(value: f64) -> Self {
+		UnitDuration::new(value, TimeUnit::Day)
+	}
+
+	pub fn weeks(value: f64) -> Self {
+		UnitDuration::new(value, TimeUnit::Week)
+	}
+
+	pub fn get_value(&self) -> f64 {
+		self.value
+	}
+
+	pub fn get_unit(&self) -> TimeUnit {
+		self.unit
+	}
+
+	/// Gets the value in another unit.
+	pub fn in_unit(&self, unit: TimeUnit, config: &UnitConfig) -> f64 {
+		self.value * config.hours(self.unit) / config.hours(unit)
+	}
+}
+
+/// Parses a number with a unit suffix, like `3d` or `2.5h`.
+impl FromStr for UnitDuration {
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err> {
+		let error = || format!("Invalid duration: {}, expected a number with h, d or w", text);
+		let split = text.len().checked_sub(1).filter(|split| text.is_char_boundary(*split)).ok_or_else(error)?;
+		let (value, unit) = text.split_at(split);
+		let unit = unit.parse::<TimeUnit>().map_err(|_| error())?;
+		let value = value.parse::<f64>().ok().filter(|value| value.is_finite()).ok_or_else(error)?;
+		Ok(UnitDuration { value, unit })
+	}
+}
+
+/// `3d`, `2.5h`.
+impl fmt::Display for UnitDuration {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}{}", self.value, self.unit)
+	}
+}
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::scheduler::Scheduler;
+
+/// Changes between two calculated schedules.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduleDiff<T> {
+	pub old_duration: T,
+	pub new_duration: T,
+	/// Critical paths of the new schedule that were not critical before.
+	pub new_critical_paths: Vec<String>,
+	/// Critical paths of the old schedule that are not critical anymore.
+	pub removed_critical_paths: Vec<String>,
+	pub added_tasks: Vec<String>,
+	pub removed_tasks: Vec<String>,
+	/// Tasks in both schedules with a different total float:
+	/// `(task ID, old float, new float)`.
+	pub float_changes: Vec<(String, T, T)>,
+}
+
+impl <T> ScheduleDiff<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	/// Compares two schedulers, both have to be in ready state.
+	pub fn between(old: &Scheduler<T>, new: &Scheduler<T>) -> Result<Self, String> {
+		Ok(ScheduleDiff::between_snapshots(&ScheduleSnapshot::of(old)?, &ScheduleSnapshot::of(new)?))
+	}
+
+	/// Compares the snapshots of two schedules, like of one scheduler
+	/// before and after an edit.
+	pub(crate) fn between_snapshots(old: &ScheduleSnapshot<T>, new: &ScheduleSnapshot<T>) -> Self {
+		let old_floats: HashMap<&String, T> = old.floats.iter().map(|(id, float)| (id, *float)).collect();
+		let new_floats: HashMap<&String, T> = new.floats.iter().map(|(id, float)| (id, *float)).collect();
+		let mut added_tasks: Vec<String> = vec!{};
+		let mut float_changes: Vec<(String, T, T)> = vec!{};
+		for (id, new_float) in &new.floats {
+			match old_floats.get(id) {
+				Some(old_float) if *old_float != *new_float => {
+					float_changes.push((id.clone(), *old_float, *new_float));
+				},
+				Some(_) => {},
+				None => added_tasks.push(id.clone()),
+			}
+		}
+		let removed_tasks: Vec<String> = old.floats.iter()
+			.map(|(id, _)| id)
+			.filter(|id| !new_floats.contains_key(id))
+			.cloned()
+			.collect();
+
+		ScheduleDiff {
+			old_duration: old.duration,
+			new_duration: new.duration,
+			new_critical_paths: new.critical_paths.iter()
+				.filter(|path| !old.critical_paths.contains(path))
+				.cloned()
+				.collect(),
+			removed_critical_paths: old.critical_paths.iter()
+				.filter(|path| !new.critical_paths.contains(path))
+				.cloned()
+				.collect(),
+			added_tasks,
+			removed_tasks,
+			float_changes,
+		}
+	}
+
+	/// True if nothing changed.
+	pub fn is_empty(&self) -> bool {
+		self.old_duration == self.new_duration
+			&& self.new_critical_paths.is_empty()
+			&& self.removed_critical_paths.is_empty()
+			&& self.added_tasks.is_empty()
+			&& self.removed_tasks.is_empty()
+			&& self.float_changes.is_empty()
+	}
+}
+
+/// The values of a calculated schedule compared by `ScheduleDiff`.
+pub(crate) struct ScheduleSnapshot<T> {
+	duration: T,
+	/// Sorted critical paths as `A -> B -> C`.
+	critical_paths: Vec<String>,
+	/// Total float of the tasks ordered by ID.
+	floats: Vec<(String, T)>,
+}
+
+impl <T> ScheduleSnapshot<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	/// Takes the values of a scheduler in ready state.
+	pub(crate) fn of(scheduler: &Scheduler<T>) -> Result<Self, String> {
+		let mut critical_paths: Vec<String> = scheduler.get_critical_paths().iter()
+			.map(|path| path.get_task_ids().join(" -> "))
+			.collect();
+		critical_paths.sort();
+		let mut floats: Vec<(String, T)> = vec!{};
+		for task in scheduler.get_tasks() {
+			floats.push((task.get_id(), task.get_total_float()?));
+		}
+		Ok(ScheduleSnapshot {
+			duration: scheduler.get_duration()?,
+			critical_paths,
+			floats,
+		})
+	}
+}
+
+/// Text rendering in a diff-like format.
+impl <T> fmt::Display for ScheduleDiff<T>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.is_empty() {
+			return writeln!(f, "No changes.");
+		}
+		if self.old_duration == self.new_duration {
+			writeln!(f, "Project duration: {}", self.new_duration)?;
+		} else {
+			writeln!(
+				f
+				, "Project duration: {} -> {} ({}{})"
+				, self.old_duration
+				, self.new_duration
+				, if self.new_duration > self.old_duration { "+" } else { "-" }
+				, if self.new_duration > self.old_duration {
+					self.new_duration - self.old_duration
+				} else {
+					self.old_duration - self.new_duration
+				}
+			)?;
+		}
+		for id in &self.added_tasks {
+			writeln!(f, "+ task {}", id)?;
+		}
+		for id in &self.removed_tasks {
+			writeln!(f, "- task {}", id)?;
+		}
+		for path in &self.removed_critical_paths {
+			writeln!(f, "- critical: {}", path)?;
+		}
+		for path in &self.new_critical_paths {
+			writeln!(f, "+ critical: {}", path)?;
+		}
+		for (id, old_float, new_float) in &self.float_changes {
+			writeln!(f, "~ float {}: {} -> {}", id, old_float, new_float)?;
+		}
+		Ok(())
+	}
+}
+
+/// Watches files by polling their modification time and size.
+/// It does not need any platform specific notification support.
+pub struct FileWatcher {
+	paths: Vec<String>,
+	interval: Duration,
+	states: HashMap<String, Option<(SystemTime, u64)>>,
+}
+
+impl FileWatcher {
+	/// Creates a watcher, the current state of the files is the baseline.
+	pub fn new(paths: Vec<String>, interval: Duration) -> Self {
+		let mut watcher = FileWatcher {
+			paths,
+			interval,
+			states: HashMap::new(),
+		};
+		for path in watcher.paths.clone() {
+			let state = file_state(&path);
+			watcher.states.insert(path, state);
+		}
+		watcher
+	}
+
+	/// Replaces the watched files, like with the included files of the last
+	/// read. The files watched before keep their state, the new ones get
+	/// their current state as the baseline.
+	pub fn set_paths(&mut self, paths: Vec<String>) {
+		self.states.retain(|path, _| paths.contains(path));
+		for path in &paths {
+			if !self.states.contains_key(path) {
+				self.states.insert(path.clone(), file_state(path));
+			}
+		}
+		self.paths = paths;
+	}
+
+	/// Gets the files changed since the last check, without blocking.
+	/// A file that is removed or created counts as changed.
+	pub fn poll(&mut self) -> Vec<String> {
+		let mut changed: Vec<String> = vec!{};
+		for path in &self.paths {
+			let state = file_state(path);
+			if self.states.get(path) != Some(&state) {
+				self.states.insert(path.clone(), state);
+				changed.push(path.clone());
+			}
+		}
+		changed
+	}
+
+	/// Blocks until at least one of the files changes, then returns the changed ones.
+	pub fn wait_for_change(&mut self) -> Vec<String> {
+		loop {
+			let changed = self.poll();
+			if !changed.is_empty() {
+				return changed;
+			}
+			thread::sleep(self.interval);
+		}
+	}
+}
+
+fn file_state(path: &str) -> Option<(SystemTime, u64)> {
+	let metadata = fs::metadata(path).ok()?;
+	Some((metadata.modified().ok()?, metadata.len()))
+}
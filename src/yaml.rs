@@ -0,0 +1,77 @@
+use std::fs;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::customtask::CustomTask;
+use crate::project::ProjectFile;
+use crate::scheduler::Scheduler;
+
+/// Parses a YAML project definition, the same schema as the JSON one.
+/// Errors start with their line and column.
+pub fn parse_str<T>(contents: &str) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ DeserializeOwned
+{
+	let project: ProjectFile<T> = serde_yaml::from_str(contents).map_err(|e| {
+		let message = e.to_string();
+		match e.location() {
+			Some(location) => {
+				// The message has the location too.
+				let position = format!(" at line {} column {}", location.line(), location.column());
+				format!(
+					"Invalid YAML project: line {}, column {}: {}"
+					, location.line()
+					, location.column()
+					, message.replacen(&position, "", 1)
+				)
+			},
+			None => format!("Invalid YAML project: {}", message),
+		}
+	})?;
+	project.into_tasks()
+}
+
+/// Reads and parses a YAML project file.
+pub fn parse_file<T>(filename: &String) -> Result<Vec<CustomTask<T>>, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ DeserializeOwned
+{
+	match fs::read_to_string(filename) {
+		Ok(contents) => parse_str(&contents)
+			.map_err(|e| format!("{}: {}", filename, e)),
+		Err(e) => Err(format!("Could not read file: {}\n\r{}", filename, e)),
+	}
+}
+
+/// Writes the tasks of a scheduler as a YAML project definition.
+pub fn write_project<T>(scheduler: &Scheduler<T>) -> Result<String, String>
+where T: From<i8>
+	+ std::clone::Clone
+	+ std::marker::Copy
+	+ std::ops::Sub::<Output = T>
+	+ std::ops::Add<Output = T>
+	+ std::fmt::Display
+	+ std::fmt::Debug
+	+ std::cmp::PartialOrd
+	+ std::ops::AddAssign
+	+ Serialize
+{
+	serde_yaml::to_string(&ProjectFile::from_scheduler(scheduler))
+		.map_err(|e| format!("Could not write YAML project: {}", e))
+}